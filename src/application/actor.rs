@@ -1,57 +1,170 @@
 /// The application's actor controls the message flow
 /// between the two participating nodes.
-use crate::game::{self, Coordinate};
-use crate::gui::{Log, LogType, Mailbox as GuiMailbox, Message as GuiMessage};
-
-use super::{gamestate::Move, ingress::Message};
-
-use regex::Regex;
-use tokio::io::{AsyncReadExt, stdin};
+use crate::config::get_transcript_path;
+use crate::events::{Event, EventSink, Side};
+use crate::game::{self, Coordinate, Ship};
+use crate::gui::{GridView, Log, LogLevel, LogType};
+use crate::strategy::{Board, HuntTarget, Strategy};
+
+use super::{
+    Channel, ChannelSender,
+    chat::{self, RateLimiter},
+    coinflip::{CoinFlip, Flip},
+    delivery::{Inbox, Outbox},
+    gamestate::Move,
+    ingress::{Command, Envelope, Mailbox, Message},
+    session::{Handling, State, session_id},
+    summary::{Score, Stats, Summary, Transcript},
+};
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use commonware_cryptography::Signer;
 use commonware_macros::select;
 use commonware_p2p::{Receiver, Recipients, Sender};
-use commonware_runtime::{ContextCell, Spawner, spawn_cell};
+use commonware_runtime::{Clock, ContextCell, Spawner, spawn_cell};
 use eyre::Context;
-use futures::SinkExt;
-use parrot::llm::Model;
+use futures::{StreamExt, channel::mpsc, stream};
 use rand::{CryptoRng, Rng};
-use tokio::time::{Duration, sleep};
+
+/// The maximum duration the strategy may take to compute a move,
+/// before it's cancelled and the fallback strategy is used.
+const TURN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The interval at which the actor drives the session, e.g. by attacking once it's the player's turn.
+const TICK_INTERVAL: Duration = Duration::from_secs(4);
+
+/// The result of a move computation, tagged with the move number it was computed for
+/// and followed by the strategy, which is handed back to the actor.
+type StrategyResult = (u16, eyre::Result<(u8, u8)>, Box<dyn Strategy>);
+
+/// A move computation that's running outside of the actor's loop.
+struct PendingMove {
+    /// The move number the computation is running for.
+    number: u16,
+    /// The time at which the computation was started.
+    started: SystemTime,
+}
 
 /// The main actor that drives the communication between the participants,
 /// while maintaining track of the game state internally.
 ///
-/// This actor uses an LLM model to compute strategic moves based on the game history,
-/// rather than making random attacks.
+/// This actor uses a [`Strategy`] (e.g. backed by an LLM model) to compute the moves
+/// based on the game history, rather than making random attacks.
 ///
 /// TODO: I guess the `crate::game::Game` could be made into its own actor
 /// as well and then receive driving updates through the channels.
-pub struct GameStateActor<R: Rng + CryptoRng + Spawner, C: Signer> {
+pub struct GameStateActor<R: Rng + CryptoRng + Spawner + Clock, C: Signer> {
     context: ContextCell<R>,
     crypto: C,
 
-    // The GUI mailbox will be used to send messages to the GUI actor.
-    gui_mailbox: GuiMailbox,
+    /// The public key of the opponent, which is the only recipient of the sent messages
+    /// and the only peer whose messages are handled.
+    ///
+    /// NOTE: other peers (e.g. spectators or lobby peers) must neither see the moves
+    /// nor interfere with the game.
+    opponent: C::PublicKey,
+
+    // The game events are published to all subscribers of the sink (e.g. the GUI actor).
+    events: EventSink,
+
+    /// The minimum level of the published logs.
+    log_level: LogLevel,
 
-    // The LLM model that's used to compute the game moves.
+    // The strategy that's used to compute the game moves.
     //
     // NOTE: we're keeping this as a Box since there's a runtime selection of the used
-    // model so this might be changing depending on the system that's running it.
+    // strategy (and LLM model) so this might be changing depending on the system that's running it.
     //
     // If we were to enforce implementing a concrete type that's implementing this crate
     // it would make sense to add another trait bound to the `GameStateActor`, but that
     // would moreso apply to a library situation, not here.
-    model: Box<dyn Model>,
+    //
+    // The strategy is moved to the blocking task that's computing the next move,
+    // so that e.g. prompting an LLM does not stall the actor's loop, and is handed back
+    // with the result.
+    //
+    // NOTE: a running computation can't be interrupted, so while the strategy did not return
+    // from a timed out computation, the moves are played by the fallback strategy instead.
+    strategy: Option<Box<dyn Strategy>>,
+
+    /// The name of the used strategy.
+    ///
+    /// NOTE: this is stored separately, since the strategy is moved out while it's computing.
+    strategy_name: String,
+
+    /// The deterministic strategy that's used if the strategy does not compute a move in time.
+    fallback: HuntTarget,
 
-    /// Signals if the player is ready to start.
-    is_ready: bool,
+    /// The currently running move computation, if any.
+    pending: Option<PendingMove>,
 
-    /// Signals if the opponent is ready to start.
-    opponent_ready: bool,
+    /// The player's part of the coin flip that decides on the first turn.
+    coin_flip: CoinFlip,
+
+    /// The opponent's commitment of the coin flip, which signals that the opponent is ready to start.
+    opponent_commitment: Option<String>,
+
+    /// The ID of the session, which is derived from both commitments of the coin flip.
+    ///
+    /// Messages of other sessions (e.g. replayed from a previous session) are dropped.
+    session: Option<String>,
+
+    /// The number of the current game in the series, which is included in every message
+    /// to drop stale messages of previous games.
+    game_number: u32,
+
+    /// The outcome of the coin flip, once both nonces were revealed.
+    ///
+    /// NOTE: this is only set for the first game of a series, since the first turn
+    /// alternates between the rematches.
+    flip: Option<Flip>,
 
     /// Signals if it's the actor's turn (or the opponent's turn if false).
     my_turn: bool,
 
+    /// The state of the session, which defines how incoming messages are handled.
+    state: State,
+
+    /// The player that has the first turn.
+    first: Option<Side>,
+
+    /// The time at which both players were ready.
+    started: Option<SystemTime>,
+
+    /// The winner of the game, once it's over.
+    winner: Option<Side>,
+
+    /// The summary of the game, once the opponent revealed their ships.
+    summary: Option<Summary>,
+
+    /// The player that proposed a rematch of the finished game, if any.
+    rematch: Option<Side>,
+
+    /// The running score of the games against the opponent.
+    series: Score,
+
+    /// The rate limit of the chat messages sent to the opponent.
+    own_chat: RateLimiter,
+
+    /// The rate limit of the chat messages received from the opponent,
+    /// where the messages exceeding it are dropped.
+    opponent_chat: RateLimiter,
+
+    /// The sent messages that were not acknowledged yet, which are retransmitted with a backoff.
+    outbox: Outbox,
+
+    /// The received messages of the opponent, which are handled in order and without duplicates.
+    ///
+    /// NOTE: the order spans both the control and the moves channel (see [`super::delivery`]).
+    inbox: Inbox,
+
+    /// The commands received e.g. from the GUI.
+    ///
+    /// NOTE: this is taken out of the actor once it's running, so that it can be polled
+    /// while handling the commands.
+    commands: Option<mpsc::Receiver<Command>>,
+
     /// The list of the exchanged moves.
     ///
     /// TODO: change to hashmap maybe for better lookup of played moves? We don't really need to read the moves in order
@@ -68,36 +181,69 @@ pub struct GameStateActor<R: Rng + CryptoRng + Spawner, C: Signer> {
     game: game::Player,
 }
 
-impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
+impl<R: Rng + CryptoRng + Spawner + Clock, C: Signer> GameStateActor<R, C> {
     /// Create new application actor.
     ///
-    /// NOTE: As opposed to many other implementations / use cases of the actor model using the
-    /// Commonware framework, we don't need to return a mailbox as output of this `new` method here,
-    /// because there is no entity sending messages to the `GameStateActor` at this present moment.
-    pub fn new(context: R, gui_mailbox: GuiMailbox, crypto: C, model: Box<dyn Model>) -> Self {
-        Self {
+    /// The returned mailbox is used to send commands to the actor, e.g. to save the transcript
+    /// of a finished game from the GUI.
+    /// All messages are only exchanged with the given opponent.
+    pub fn new(
+        context: R,
+        events: EventSink,
+        log_level: LogLevel,
+        crypto: C,
+        opponent: C::PublicKey,
+        strategy: Box<dyn Strategy>,
+    ) -> (Self, Mailbox) {
+        let (sender, receiver) = mpsc::channel(1);
+        let mut context = context;
+        let coin_flip = CoinFlip::new(&mut context);
+
+        let actor = Self {
             context: ContextCell::new(context),
             crypto,
+            opponent,
 
-            gui_mailbox,
-            model,
+            events,
+            log_level,
+            strategy_name: strategy.name().to_string(),
+            strategy: Some(strategy),
+            fallback: HuntTarget::new(),
+            pending: None,
 
             // Game logic
             my_turn: false,
+            state: State::Connecting,
+            first: None,
+            started: None,
+            winner: None,
+            summary: None,
+            rematch: None,
+            series: Score::default(),
+            own_chat: RateLimiter::default(),
+            opponent_chat: RateLimiter::default(),
+            outbox: Outbox::default(),
+            inbox: Inbox::default(),
+            commands: Some(receiver),
+
+            coin_flip,
+            opponent_commitment: None,
+            session: None,
+            game_number: 1,
+            flip: None,
 
-            is_ready: false,
             moves: Vec::new(),
-
-            opponent_ready: false,
             opponent_moves: Vec::new(),
 
             game: game::Player::new(),
-        }
+        };
+
+        (actor, Mailbox::new(sender))
     }
 
     pub fn start(
         mut self,
-        sender: impl Sender<PublicKey = C::PublicKey>,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
         receiver: impl Receiver<PublicKey = C::PublicKey>,
     ) {
         spawn_cell!(self.context, self.run(sender, receiver).await);
@@ -105,69 +251,294 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
 
     pub async fn run(
         mut self,
-        sender: impl Sender<PublicKey = C::PublicKey>,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
         mut receiver: impl Receiver<PublicKey = C::PublicKey>,
     ) {
+        // The computed moves are sent back to the actor's loop through this channel,
+        // so that incoming messages are still handled while the strategy is running.
+        let (results_sender, mut results_receiver) = mpsc::channel::<StrategyResult>(1);
+
+        // NOTE: the commands are chained with a pending stream, so that the loop is not woken up
+        // repeatedly once all mailboxes are dropped (e.g. in headless mode).
+        let mut commands = self
+            .commands
+            .take()
+            .expect("actor must only be run once")
+            .chain(stream::pending());
+
+        // The own grid is already known before the game starts, so we're drawing it right away.
+        if let Err(e) = self.draw_grid().await {
+            self.end_game_with_log(LogType::Error, &format!("failed to draw grid: {}", e))
+                .await;
+        }
+
+        // The ready message is retransmitted until the opponent acknowledged it,
+        // e.g. if the opponent is not connected yet.
+        if let Err(e) = self.send_ready(sender.clone()).await {
+            self.end_game_with_log(
+                LogType::Error,
+                &format!("failed to send ready message: {}", e),
+            )
+            .await;
+        }
+
+        // NOTE: the stop signal is taken before the loop, so that it's not missed
+        // while handling a message.
+        let mut stopped = self.context.as_present().stopped();
+
+        // NOTE: the clock is cloned, so that the tick does not borrow the actor in the loop.
+        let clock = self.context.as_present().clone();
+
         loop {
+            // NOTE: without pending messages, the loop is woken up by the tick anyway.
+            let retransmission = self
+                .outbox
+                .deadline()
+                .unwrap_or_else(|| clock.current() + TICK_INTERVAL);
+
             select! {
+                // The runtime is stopped once the user quits the TUI.
+                _ = &mut stopped => {
+                    self.cancel_pending();
+                    break;
+                },
                 // We're waiting to receive an incoming message from the opponent
                 msg = receiver.recv() => {
                     match msg {
-                        Ok((_, message_bytes)) => {
-                            if let Err(e) = self.handle_message(
+                        Ok((peer, message_bytes)) => {
+                            if let Err(e) = self.receive(
                                 sender.clone(),
-                                Message::from(message_bytes)
+                                peer,
+                                message_bytes
                             ).await
                             { self.end_game_with_log(LogType::Error, &format!("got error: {:?}", e)).await };
                         },
-                        Err(_) => self.end_game_with_log(LogType::Error, "failed to receive message").await,
+                        // NOTE: once the receiver is closed (e.g. after the game manager stopped),
+                        // every later receive fails right away, so the actor is stopped.
+                        Err(_) => {
+                            self.end_game_with_log(LogType::Error, "failed to receive message").await;
+                            break;
+                        },
                     }
                 },
-                _ = sleep(Duration::from_secs(4)) => {
-                    if !self.game_ready() {
-                        self.must_log(LogType::Debug, "game not ready yet; sending ready message to other player")
-                            .await;
-
-                        if let Err(e) = self
-                            .send(sender.clone(), Message::Ready)
-                            .await {
-                                self.end_game_with_log(LogType::Error, &format!("failed to send ready message: {}", e)).await;
-                            }
-
-                        self.my_turn = true; // we're having the first sender of the ready message have the first turn.
-                        self.is_ready = true;
-                    } else if self.my_turn
-                        && let Err(e) = &self.attack(sender.clone()).await {
+                command = commands.next() => {
+                    if let Some(command) = command
+                        && let Err(e) = self.handle_command(sender.clone(), command).await {
+                            self.log(LogType::Error, &format!("failed to handle command: {}", e)).await;
+                        }
+                },
+                // We're waiting for the strategy to finish computing the next move
+                result = results_receiver.next() => {
+                    if let Some((number, result, strategy)) = result
+                        && let Err(e) = self.handle_strategy_result(sender.clone(), number, result, strategy).await {
                             self.end_game_with_log(LogType::Error, &format!("failed to attack: {}", e)).await;
-                        };
+                        }
+                },
+                _ = clock.sleep_until(retransmission) => {
+                    if let Err(e) = self.retransmit(sender.clone()).await {
+                        self.end_game_with_log(LogType::Error, &format!("failed to retransmit messages: {}", e)).await;
+                    }
+                },
+                _ = clock.sleep(TICK_INTERVAL) => {
+                    match self.state {
+                        State::Connecting => {
+                            self.log(LogType::Debug, "game not ready yet; waiting for the ready message of the other player")
+                                .await;
+                        }
+                        State::Playing if self.my_turn => {
+                            if let Err(e) = &self.attack(sender.clone(), results_sender.clone()).await {
+                                self.end_game_with_log(LogType::Error, &format!("failed to attack: {}", e)).await;
+                            }
+                        }
+                        // We're waiting for the opponent, or the game is over.
+                        _ => (),
+                    }
                 }
             }
         }
     }
 
-    /// Sends a computed move for the game via the p2p layer.
-    async fn attack(&mut self, sender: impl Sender<PublicKey = C::PublicKey>) -> eyre::Result<()> {
-        let mut unused = false;
+    /// Drives the computation of the next move.
+    ///
+    /// If no computation is running yet, the strategy is spawned on a blocking task.
+    /// If the running computation exceeds the [`TURN_TIMEOUT`], it is cancelled
+    /// and the move of the fallback strategy is played instead.
+    /// The fallback strategy is used right away as well, as long as the strategy
+    /// is still stuck in a previous computation.
+    async fn attack(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        results: mpsc::Sender<StrategyResult>,
+    ) -> eyre::Result<()> {
+        let Some(pending) = &self.pending else {
+            return match self.strategy.take() {
+                Some(strategy) => self.compute_move(strategy, results).await,
+                None => {
+                    let reason = format!(
+                        "{} strategy is still computing a previous move; using fallback strategy",
+                        self.strategy_name
+                    );
+                    self.play_fallback(sender, &reason).await
+                }
+            };
+        };
+
+        let elapsed = self
+            .context
+            .as_present()
+            .current()
+            .duration_since(pending.started)
+            .unwrap_or_default();
+        if elapsed < TURN_TIMEOUT {
+            return Ok(());
+        }
+
+        self.cancel_pending();
+        let reason = format!(
+            "{} strategy timed out; using fallback strategy",
+            self.strategy_name
+        );
+        self.play_fallback(sender, &reason).await
+    }
+
+    /// Plays the move of the fallback strategy, logging the given reason.
+    async fn play_fallback(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        reason: &str,
+    ) -> eyre::Result<()> {
+        self.log(LogType::Info, reason).await;
+
+        let board = Board::new(
+            self.game.opponent_grid.width,
+            self.game.opponent_grid.height,
+            &self.moves,
+        );
+        let (x, y) = self.fallback.next_move(&board)?;
 
-        // NOTE: the existing battleship-rs logic uses indices from 1..=grid_size.
-        let mut x: u8 = 1;
-        let mut y: u8 = 1;
+        self.play_move(sender, x, y).await
+    }
+
+    /// Spawns the computation of the next move on a blocking task.
+    ///
+    /// The result is sent through the given channel once the strategy returns,
+    /// along with the strategy itself.
+    async fn compute_move(
+        &mut self,
+        mut strategy: Box<dyn Strategy>,
+        mut results: mpsc::Sender<StrategyResult>,
+    ) -> eyre::Result<()> {
+        let number = self.next_move();
+        let moves = self.moves.clone();
+        let (width, height) = (
+            self.game.opponent_grid.width,
+            self.game.opponent_grid.height,
+        );
+
+        self.context
+            .as_present()
+            .clone()
+            .spawn_blocking(true, move |_| {
+                let board = Board::new(width, height, &moves);
+                let result = strategy.next_move(&board);
+
+                // NOTE: the receiver is only dropped once the actor stops, so we ignore the error here.
+                let _ = results.try_send((number, result, strategy));
+            });
+
+        self.pending = Some(PendingMove {
+            number,
+            started: self.context.as_present().current(),
+        });
+
+        self.log(
+            LogType::Debug,
+            &format!(
+                "computing move {} using {} strategy",
+                number, self.strategy_name
+            ),
+        )
+        .await;
+        Ok(())
+    }
 
-        // TODO: instead of generating this in a loop we can have a vector of all
-        // possible moves and then only calculate one random to take from the slice.
-        // On every move the used move is removed from the slice.
-        while !unused {
-            (x, y) = self.prompt_for_next_move().await?;
+    /// Cancels the running move computation, if any.
+    ///
+    /// NOTE: a strategy that's blocking inside of a computation cannot be interrupted,
+    /// so it keeps running until it returns, but its result is discarded.
+    fn cancel_pending(&mut self) {
+        self.pending = None;
+    }
 
+    /// Plays the move that was computed by the strategy.
+    ///
+    /// Results of cancelled computations or for outdated moves are discarded,
+    /// while the strategy is taken back and its logs are published in any case.
+    async fn handle_strategy_result(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        number: u16,
+        result: eyre::Result<(u8, u8)>,
+        mut strategy: Box<dyn Strategy>,
+    ) -> eyre::Result<()> {
+        let logs = strategy.take_logs();
+        self.strategy = Some(strategy);
+
+        for log in logs {
+            self.log(log.log_type(), log.content()).await;
+        }
+
+        if self
+            .pending
+            .as_ref()
+            .is_none_or(|pending| pending.number != number)
+        {
             self.log(
                 LogType::Debug,
-                &format!("generated new attack point: ({},{})", x, y),
+                &format!("discarding outdated result for move {}", number),
             )
-            .await?;
+            .await;
+            return Ok(());
+        }
+
+        self.pending = None;
+        let (x, y) = result?;
 
-            unused = !self.moves.iter().any(|m| m.get_x() == x && m.get_y() == y)
+        self.play_move(sender, x, y).await
+    }
+
+    /// Sends the given move for the game via the p2p layer.
+    async fn play_move(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        x: u8,
+        y: u8,
+    ) -> eyre::Result<()> {
+        // NOTE: the existing battleship-rs logic uses indices from 1..=grid_size.
+        let board = Board::new(
+            self.game.opponent_grid.width,
+            self.game.opponent_grid.height,
+            &self.moves,
+        );
+
+        if !board.contains(x, y) || board.is_played(x, y) {
+            return Err(eyre::eyre!(
+                "strategy {} returned invalid move: ({},{})",
+                self.strategy_name,
+                x,
+                y
+            ));
         }
 
+        self.log(
+            LogType::Debug,
+            &format!(
+                "generated new attack point using {} strategy: ({},{})",
+                self.strategy_name, x, y
+            ),
+        )
+        .await;
+
         // NOTE: we're initializing the move as false since we don't know yet if this was successful or not.
         // It will be updated once we receive confirmation from the other peer.
         let current_move = Move::new(self.next_move(), x, y, false);
@@ -180,8 +551,9 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
             LogType::Debug,
             &format!("sending attack message: {:?}", msg),
         )
-        .await?;
+        .await;
         self.send(sender, msg).await?;
+        self.publish(Event::attack(Side::Own, &current_move)).await;
 
         self.moves.push(current_move);
         self.my_turn = false;
@@ -190,46 +562,105 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
     }
 
     async fn draw_grid(&mut self) -> eyre::Result<()> {
-        let full_grid = [
-            self.game.opponent_grid.as_string(false)?,
-            self.game.grid.as_string(true)?,
-        ]
-        .join("\n");
-
-        self.gui_mailbox
-            .sender
-            .send(GuiMessage::Draw { grid: full_grid })
-            .await?;
+        let last_move = |moves: &[Move]| moves.last().map(|m| (m.get_x(), m.get_y()));
+
+        let own = GridView::new(self.game.own_cells(), last_move(&self.opponent_moves));
+        let opponent = GridView::new(
+            self.game.opponent_grid.cells(false),
+            last_move(&self.moves),
+        );
 
+        self.publish(Event::Grids { own, opponent }).await;
         Ok(())
     }
 
+    /// Ends the game with the given log.
+    ///
+    /// The actor keeps running until the user quits the TUI, so that the final state can still be inspected.
     async fn end_game_with_log(&mut self, log_type: LogType, content: &str) -> () {
-        self.must_log(log_type, content).await;
+        // Errors after the game ended (e.g. while revealing the ships) don't change the outcome.
+        if self.state == State::Finished {
+            return self.log(log_type, content).await;
+        }
 
-        let mut input = vec![];
-        let _ = stdin()
-            .read(&mut input)
-            .await
-            .expect("failed to receive user input");
-        std::process::exit(0);
+        self.cancel_pending();
+        self.state = State::Finished;
+
+        if log_type == LogType::Error {
+            self.publish(Event::Error {
+                message: content.to_string(),
+            })
+            .await;
+        }
+
+        self.winner = match log_type {
+            LogType::Won => Some(Side::Own),
+            LogType::Lost => Some(Side::Opponent),
+            _ => None,
+        };
+        if let Some(winner) = self.winner {
+            self.series.record(winner);
+        }
+        self.publish(Event::End {
+            winner: self.winner,
+        })
+        .await;
+        self.log(log_type, content).await;
+    }
+
+    /// Places the ships for the next game and signals it to the opponent.
+    ///
+    /// The game starts once the opponent placed their ships as well.
+    async fn place_ships(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+    ) -> eyre::Result<()> {
+        self.state = State::Placing;
+        self.draw_grid().await?;
+
+        self.send(sender, Message::Placed).await
     }
 
-    /// Checks if the game is ready to be played.
-    fn game_ready(&self) -> bool {
-        self.is_ready && self.opponent_ready
+    /// Starts the game once both players placed their ships.
+    async fn start_game(&mut self) -> eyre::Result<()> {
+        let first = self
+            .first
+            .ok_or_else(|| eyre::eyre!("first turn was not decided"))?;
+
+        self.state = State::Playing;
+        self.my_turn = first == Side::Own;
+        self.started = Some(self.context.as_present().current());
+
+        self.publish(Event::Start { first }).await;
+        Ok(())
+    }
+
+    /// Sends the ready message, which contains the commitment of the coin flip.
+    async fn send_ready(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+    ) -> eyre::Result<()> {
+        let commitment = self.coin_flip.commitment().to_string();
+        self.send(sender, Message::Ready { commitment }).await?;
+
+        self.publish(Event::Handshake { from: Side::Own }).await;
+        Ok(())
     }
 
     /// Updates the internal game state when receiving an incoming message.
     async fn handle_attack(
         &mut self,
         message: Message,
-        sender: impl Sender<PublicKey = C::PublicKey>,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
     ) -> eyre::Result<()> {
         message.validate()?;
 
         match message {
             Message::Attack { m } => {
+                if self.my_turn {
+                    return Err(eyre::eyre!("opponent attacked out of turn"));
+                }
+
                 // we're only allowing monotonically increasing numbers, incremented by 1, here
                 if m.get_number() as usize != self.moves.len() + self.opponent_moves.len() + 1 {
                     return Err(eyre::eyre!(
@@ -248,9 +679,20 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
                     return Err(eyre::eyre!("move already played"));
                 }
 
+                self.publish(Event::attack(Side::Opponent, &m)).await;
+
                 let is_hit = self.game.handle_attack(m.get_x(), m.get_y());
-                self.opponent_moves
-                    .push(Move::new(m.get_number(), m.get_x(), m.get_y(), is_hit));
+                let played = Move::new(m.get_number(), m.get_x(), m.get_y(), is_hit);
+                self.publish(Event::result(Side::Opponent, &played)).await;
+                if let Some(ship) = self.game.sunk_ship(m.get_x(), m.get_y()) {
+                    self.publish(Event::Sunk {
+                        by: Side::Opponent,
+                        ship: ship.name().to_string(),
+                    })
+                    .await;
+                }
+
+                self.opponent_moves.push(played);
                 self.my_turn = true;
 
                 // Upon handling an attack we're sending the instruction
@@ -264,20 +706,14 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
                             LogType::OpponentHit,
                             &format!("💥 {}: opponent attack hit", m.get_position()),
                         )
-                        .await?;
+                        .await;
                         self.send(sender.clone(), Message::Hit { m: m.clone() })
                             .await?;
                         if self.game.lost() {
-                            self.send(sender, Message::EndGame).await?;
-
-                            self.log(
-                                LogType::Lost,
-                                "💔💔💔 you lost the game; press any key to exit the game 💔💔💔",
-                            )
-                            .await?;
-                            let mut input = vec![];
-                            let _ = stdin().read(&mut input).await?;
-                            std::process::exit(0);
+                            self.send(sender.clone(), Message::EndGame).await?;
+                            self.end_game_with_log(LogType::Lost, "💔💔💔 you lost the game 💔💔💔")
+                                .await;
+                            self.reveal(sender).await?;
                         }
                     }
                     false => {
@@ -285,7 +721,7 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
                             LogType::OpponentMiss,
                             &format!("💦 {}: opponent attack missed", m.get_position()),
                         )
-                        .await?;
+                        .await;
                         self.send(sender, Message::Miss { m }).await?
                     }
                 };
@@ -296,76 +732,560 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
         }
     }
 
+    /// Acknowledges the received envelope and handles the messages that are now in order.
+    ///
+    /// The acknowledgements and the chat messages of the opponent are handled right away,
+    /// since they're not sequenced.
+    /// The messages of any other peer than the opponent are ignored.
+    async fn receive(
+        &mut self,
+        mut sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        peer: C::PublicKey,
+        message_bytes: bytes::Bytes,
+    ) -> eyre::Result<()> {
+        // NOTE: the sender is checked before decoding the message, so that other peers
+        // can't make the game fail with malformed messages.
+        if peer != self.opponent {
+            self.log(
+                LogType::Debug,
+                &format!("ignoring message of {}, which is not the opponent", peer),
+            )
+            .await;
+            return Ok(());
+        }
+
+        let envelope = match Envelope::try_from(message_bytes) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                self.log(
+                    LogType::Debug,
+                    &format!("dropping malformed message of the opponent: {}", e),
+                )
+                .await;
+                return Ok(());
+            }
+        };
+        if let Message::Ack { seq } = envelope.message {
+            if !self.outbox.ack(seq) {
+                self.log(
+                    LogType::Debug,
+                    &format!("ignoring acknowledgement of unknown message {}", seq),
+                )
+                .await;
+            }
+            return Ok(());
+        }
+
+        // The chat messages are not sequenced, so they're handled right away without an acknowledgement.
+        if envelope.message.channel() == Channel::Chat {
+            return self.handle_message(sender, envelope).await;
+        }
+
+        let seq = envelope.seq;
+        let Some(envelopes) = self.inbox.receive(envelope) else {
+            self.log(
+                LogType::Debug,
+                &format!("dropping message {} ahead of the receive window", seq),
+            )
+            .await;
+            return Ok(());
+        };
+
+        // NOTE: duplicates are acknowledged as well, since the previous acknowledgement might have been lost.
+        let message = Message::Ack { seq };
+        let channel = message.channel();
+        let ack = Envelope {
+            session: self.session.clone(),
+            game: self.game_number,
+            seq: 0,
+            message,
+        };
+        if let Err(e) = sender
+            .send(channel, Recipients::One(peer), ack.into(), false)
+            .await
+        {
+            return Err(e).wrap_err("failed to send acknowledgement");
+        }
+
+        if envelopes.is_empty() {
+            self.log(
+                LogType::Debug,
+                &format!(
+                    "message {} is a duplicate or waits for earlier messages",
+                    seq
+                ),
+            )
+            .await;
+            return Ok(());
+        }
+        for envelope in envelopes {
+            self.handle_message(sender.clone(), envelope).await?;
+        }
+
+        Ok(())
+    }
+
     /// This method implements the main application logic for any incoming messages.
     /// This includes the attacks, information about player readiness, as well as the message
     /// to communicate the game ending.
     async fn handle_message(
         &mut self,
-        sender: impl Sender<PublicKey = C::PublicKey>,
-        msg: Message,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        envelope: Envelope,
     ) -> eyre::Result<()> {
+        // The session is only agreed on once the ready messages were exchanged,
+        // so these are handled regardless of the session they were sent in.
+        let msg = envelope.message;
+        if !matches!(msg, Message::Ready { .. })
+            && (envelope.session != self.session || envelope.game != self.game_number)
+        {
+            // NOTE: these are expected after a rematch or reconnect, so they're no protocol violation.
+            self.log(
+                LogType::Debug,
+                &format!(
+                    "dropping message of session {} (game {}): {:?}",
+                    envelope.session.as_deref().unwrap_or("none"),
+                    envelope.game,
+                    msg
+                ),
+            )
+            .await;
+            return Ok(());
+        }
+
+        match self.state.handling(&msg) {
+            Handling::Handle => (),
+            Handling::Ignore => {
+                self.log(
+                    LogType::Debug,
+                    &format!("ignoring message while {}: {:?}", self.state, msg),
+                )
+                .await;
+                return Ok(());
+            }
+            Handling::Reject => {
+                return Err(eyre::eyre!(
+                    "unexpected message while {}: {:?}",
+                    self.state,
+                    msg
+                ));
+            }
+        }
+
         match msg {
             Message::Attack { m: _ } => {
-                if !self.game_ready() {
-                    return Err(eyre::eyre!("game not ready yet; can't process attack"));
-                }
-
                 self.log(LogType::Debug, &format!("handling attack: {:?}", msg))
-                    .await?;
+                    .await;
                 self.handle_attack(msg, sender).await?;
             }
             Message::EndGame => {
-                self.log(
-                    LogType::Lost,
-                    "👑👑👑 you won the game; press any key to exit the game 👑👑👑",
-                )
-                .await?;
-                let mut input = vec![];
-                let _ = stdin().read(&mut input).await?;
-                std::process::exit(0);
+                self.end_game_with_log(LogType::Won, "👑👑👑 you won the game 👑👑👑")
+                    .await;
+                self.reveal(sender).await?;
+            }
+            Message::Reveal { ships } => self.handle_reveal(ships).await?,
+            Message::Rematch => match self.rematch {
+                None => {
+                    self.rematch = Some(Side::Opponent);
+                    self.log(
+                        LogType::Info,
+                        "opponent proposed a rematch; press r to accept",
+                    )
+                    .await;
+                }
+                // Both players proposed a rematch at the same time, so it's accepted by both.
+                Some(Side::Own) => self.start_rematch(sender).await?,
+                Some(Side::Opponent) => {
+                    return Err(eyre::eyre!("opponent already proposed a rematch"));
+                }
+            },
+            Message::AcceptRematch => {
+                if self.rematch != Some(Side::Own) {
+                    return Err(eyre::eyre!("opponent accepted a rematch that was not proposed"));
+                }
+
+                self.start_rematch(sender).await?;
             }
             Message::Hit { m } => self.update_opponent_grid(m, true).await?,
             Message::Miss { m } => self.update_opponent_grid(m, false).await?,
-            Message::Ready => {
-                self.log(LogType::Debug, "received ready message").await?;
-                assert!(!self.game_ready(), "game is already marked as ready");
-
-                // We're sending a Ready message back so that the opponent is also informed of our readiness.
-                // In case, `self.my_turn` is already true, this means that the received `Ready` message is the
-                // other players' response to our initial readiness communication, so there is no need to again
-                // signal readiness via P2P.
-
-                if !self.my_turn {
-                    self.log(LogType::Debug, "sending ready message back")
-                        .await?;
-                    self.send(sender.clone(), Message::Ready).await?;
+            Message::Ready { commitment } => {
+                self.log(LogType::Debug, "received ready message").await;
+
+                // The same commitment might be sent again (e.g. replayed), so duplicates are ignored.
+                match &self.opponent_commitment {
+                    Some(previous) if *previous == commitment => return Ok(()),
+                    Some(_) => return Err(eyre::eyre!("opponent changed coin flip commitment")),
+                    None => (),
                 }
 
-                self.opponent_ready = true;
-                self.is_ready = true;
+                self.publish(Event::Handshake {
+                    from: Side::Opponent,
+                })
+                .await;
+                let session = session_id(self.coin_flip.commitment(), &commitment);
+                self.log(LogType::Info, &format!("🤝 started session {}", session))
+                    .await;
+
+                self.opponent_commitment = Some(commitment);
+                self.session = Some(session);
+                self.state = State::Handshaking;
+
+                // Once both commitments are exchanged, the nonce of the coin flip can be revealed.
+                // The messages are handled in order of their sequence numbers, so the opponent
+                // handles our commitment first, even if it has to be retransmitted.
+                self.send(
+                    sender,
+                    Message::Flip {
+                        nonce: self.coin_flip.nonce(),
+                    },
+                )
+                .await?;
             }
+            Message::Flip { nonce } => {
+                let Some(commitment) = &self.opponent_commitment else {
+                    return Err(eyre::eyre!("received coin flip before commitment"));
+                };
+                let flip = self.coin_flip.resolve(commitment, &nonce)?;
+
+                self.log(
+                    LogType::Info,
+                    &format!(
+                        "🪙 coin flip (own nonce {}, opponent nonce {}): {} the first turn",
+                        flip.own_nonce,
+                        flip.opponent_nonce,
+                        match flip.first {
+                            Side::Own => "you have",
+                            Side::Opponent => "the opponent has",
+                        }
+                    ),
+                )
+                .await;
+
+                self.first = Some(flip.first);
+                self.flip = Some(flip);
+                self.place_ships(sender).await?;
+            }
+            Message::Placed => self.start_game().await?,
+            Message::Chat { text } => self.handle_chat(text).await?,
+            // NOTE: the acknowledgements are already handled when they're received.
+            Message::Ack { .. } => (),
+        }
+
+        Ok(())
+    }
+
+    /// Publishes a chat message of the opponent.
+    ///
+    /// NOTE: invalid messages or messages exceeding the rate limit are only dropped,
+    /// since the chat must not end the game.
+    async fn handle_chat(&mut self, text: String) -> eyre::Result<()> {
+        if let Err(e) = chat::validate(&text) {
+            self.log(LogType::Error, &format!("dropping chat message: {}", e))
+                .await;
+            return Ok(());
+        }
+        let now = self.context.as_present().current();
+        if !self.opponent_chat.check(now) {
+            self.log(
+                LogType::Debug,
+                "dropping chat message: opponent exceeded the rate limit",
+            )
+            .await;
+            return Ok(());
+        }
+
+        self.publish(Event::Chat {
+            by: Side::Opponent,
+            text,
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Sends a chat message to the opponent, once the session was agreed on.
+    async fn chat(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        text: String,
+    ) -> eyre::Result<()> {
+        if self.session.is_none() {
+            return Err(eyre::eyre!("no session was established"));
+        }
+        let text = text.trim().to_string();
+        chat::validate(&text)?;
+        let now = self.context.as_present().current();
+        if !self.own_chat.check(now) {
+            return Err(eyre::eyre!("too many chat messages; wait a moment"));
         }
 
+        self.send(sender, Message::Chat { text: text.clone() })
+            .await?;
+        self.publish(Event::Chat {
+            by: Side::Own,
+            text,
+        })
+        .await;
         Ok(())
     }
 
-    async fn log(&mut self, log_type: LogType, content: &str) -> eyre::Result<()> {
-        // if log_type == LogType::Debug {
-        //     // TODO: this could be extended to use a cli flag to set the allowed log level, but for now this is fine.
-        //     return Ok(());
-        // }
+    /// Reveals the player's ships to the opponent once the game is over.
+    async fn reveal(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+    ) -> eyre::Result<()> {
+        let ships = self
+            .game
+            .grid
+            .ships
+            .iter()
+            .map(|ship| (ship.type_, ship.coords.iter().map(|c| (c.x, c.y)).collect()))
+            .collect();
+
+        self.send(sender, Message::Reveal { ships }).await
+    }
+
+    /// Reconstructs the opponent's board from the revealed ships and publishes the summary of the game.
+    ///
+    /// The revealed ships must form a standard fleet and are checked against the results of the
+    /// player's attacks, to detect an opponent that did not report the results truthfully.
+    /// Otherwise, the reveal is rejected and the summary is marked as disputed.
+    async fn handle_reveal(
+        &mut self,
+        ships: Vec<(game::ShipType, Vec<(u8, u8)>)>,
+    ) -> eyre::Result<()> {
+        let Some(winner) = self.winner else {
+            return Err(eyre::eyre!("opponent revealed ships before the game ended"));
+        };
+        if self.summary.is_some() {
+            return Err(eyre::eyre!("opponent already revealed ships"));
+        }
 
-        self.gui_mailbox
-            .sender
-            .send(GuiMessage::Log {
-                log: Log::new(log_type, content.into()),
+        let ships = ships
+            .into_iter()
+            .map(|(type_, coords)| {
+                Ship::new(type_, coords.into_iter().map(Coordinate::from).collect())
             })
-            .await
-            .map_err(|e| e.into())
+            .collect();
+        let revealed = game::Player::from_ships(ships).and_then(|mut opponent| {
+            let mut truthful = true;
+            for m in &self.moves {
+                truthful &= opponent.handle_attack(m.get_x(), m.get_y()) == m.is_hit;
+            }
+            if !truthful {
+                return Err(eyre::eyre!("ships do not match the results of the attacks"));
+            }
+
+            Ok(opponent)
+        });
+
+        // NOTE: a rejected reveal still ends the game, but the summary is marked as disputed
+        // and only shows the results of the own attacks on the opponent's board.
+        let (own, opponent_board, disputed) = match revealed {
+            Ok(opponent) => (
+                Stats::new(&self.moves, &opponent.grid.ships),
+                opponent.own_cells(),
+                false,
+            ),
+            Err(e) => {
+                self.log(
+                    LogType::Error,
+                    &format!("rejecting the revealed ships of the opponent: {}", e),
+                )
+                .await;
+
+                (
+                    Stats::new(&self.moves, &[]),
+                    self.game.opponent_grid.cells(false),
+                    true,
+                )
+            }
+        };
+
+        let summary = Summary {
+            winner,
+            duration: self
+                .started
+                .and_then(|started| {
+                    self.context
+                        .as_present()
+                        .current()
+                        .duration_since(started)
+                        .ok()
+                })
+                .unwrap_or_default(),
+            own,
+            opponent: Stats::new(&self.opponent_moves, &self.game.grid.ships),
+            series: self.series,
+            own_board: self.game.own_cells(),
+            opponent_board,
+            disputed,
+        };
+
+        self.summary = Some(summary.clone());
+        self.publish(Event::GameOver { summary }).await;
+        Ok(())
+    }
+
+    /// Handles a command that was sent to the actor's mailbox.
+    async fn handle_command(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        command: Command,
+    ) -> eyre::Result<()> {
+        match command {
+            Command::SaveTranscript => {
+                let path = self.save_transcript()?;
+                self.log(LogType::Info, &format!("saved transcript to {}", path))
+                    .await;
+                Ok(())
+            }
+            Command::Rematch => self.propose_rematch(sender).await,
+            Command::Chat(text) => self.chat(sender, text).await,
+        }
+    }
+
+    /// Proposes a rematch to the opponent, or accepts the rematch proposed by the opponent.
+    async fn propose_rematch(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+    ) -> eyre::Result<()> {
+        if self.state != State::Finished {
+            return Err(eyre::eyre!("game is not over yet"));
+        }
+
+        match self.rematch {
+            None => {
+                self.send(sender, Message::Rematch).await?;
+                self.rematch = Some(Side::Own);
+                self.log(
+                    LogType::Info,
+                    "proposed a rematch; waiting for the opponent",
+                )
+                .await;
+                Ok(())
+            }
+            Some(Side::Own) => {
+                self.log(
+                    LogType::Info,
+                    "already proposed a rematch; waiting for the opponent",
+                )
+                .await;
+                Ok(())
+            }
+            Some(Side::Opponent) => {
+                self.send(sender.clone(), Message::AcceptRematch).await?;
+                self.start_rematch(sender).await
+            }
+        }
+    }
+
+    /// Resets the game state for a new game against the same opponent.
+    ///
+    /// The p2p connection is kept and the players are already known to be ready,
+    /// so the ships are placed right away, with the first turn alternating between the games.
+    async fn start_rematch(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+    ) -> eyre::Result<()> {
+        let first = match self.first {
+            Some(Side::Own) => Side::Opponent,
+            _ => Side::Own,
+        };
+
+        self.cancel_pending();
+        self.game_number += 1;
+        self.game = game::Player::new();
+        self.moves.clear();
+        self.opponent_moves.clear();
+        self.winner = None;
+        self.summary = None;
+        self.rematch = None;
+        self.flip = None;
+        self.first = Some(first);
+
+        self.log(
+            LogType::Info,
+            &format!(
+                "starting game {} of the series (you {} - {} opponent); {} the first turn",
+                self.game_number,
+                self.series.wins,
+                self.series.losses,
+                match first {
+                    Side::Own => "you have",
+                    Side::Opponent => "the opponent has",
+                }
+            ),
+        )
+        .await;
+
+        self.place_ships(sender).await
+    }
+
+    /// Saves the transcript of the finished game and returns its path.
+    fn save_transcript(&self) -> eyre::Result<String> {
+        if self.state != State::Finished {
+            return Err(eyre::eyre!("game is not over yet"));
+        }
+        let session = self
+            .session
+            .clone()
+            .ok_or_else(|| eyre::eyre!("no session was established"))?;
+
+        let started = self
+            .started
+            .unwrap_or_else(|| self.context.as_present().current())
+            .duration_since(UNIX_EPOCH)?
+            .as_secs();
+
+        // The moves of both players are alternating, so they're ordered by their number.
+        let mut moves = self
+            .moves
+            .iter()
+            .map(|m| (m.get_number(), Event::result(Side::Own, m)))
+            .chain(
+                self.opponent_moves
+                    .iter()
+                    .map(|m| (m.get_number(), Event::result(Side::Opponent, m))),
+            )
+            .collect::<Vec<(u16, Event)>>();
+        moves.sort_by_key(|(number, _)| *number);
+
+        let path = get_transcript_path(
+            &session,
+            self.game_number,
+            &self.crypto.public_key().to_string(),
+        );
+        let transcript = Transcript {
+            session,
+            game: self.game_number,
+            started,
+            first: self.first,
+            coin_flip: self.flip.clone(),
+            moves: moves.into_iter().map(|(_, event)| event).collect(),
+            summary: self.summary.clone(),
+        };
+
+        transcript.export(&path)?;
+
+        Ok(path)
     }
 
-    async fn must_log(&mut self, log_type: LogType, content: &str) -> () {
-        self.log(log_type, content).await.expect("failed to log");
+    async fn log(&mut self, log_type: LogType, content: &str) {
+        if log_type.level() < self.log_level {
+            return;
+        }
+
+        self.publish(Event::Log {
+            log: Log::new(log_type, content.into()),
+        })
+        .await;
+    }
+
+    /// Publishes the given event to all subscribers.
+    ///
+    /// NOTE: publishing is best-effort, so the game goes on if a subscriber (e.g. the GUI) stopped.
+    async fn publish(&mut self, event: Event) {
+        self.events.publish(event).await;
     }
 
     /// Incrememnts the latest seen move number to yield the next number to play.
@@ -373,79 +1293,67 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
         (self.moves.len() + self.opponent_moves.len() + 1) as u16
     }
 
-    /// Sends a given message to all recipients.
+    /// Sends a given message to the opponent.
     async fn send(
         &mut self,
-        mut sender: impl Sender<PublicKey = C::PublicKey>,
+        mut sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
         message: Message,
     ) -> eyre::Result<()> {
         self.log(
             LogType::Debug,
-            &format!("sending message to peers: {:?}", message),
+            &format!("sending message to opponent: {:?}", message),
         )
-        .await?;
+        .await;
+
+        // NOTE: the chat messages are sent best-effort, so that a lost chat message can't
+        // hold back the moves until it's retransmitted.
+        let channel = message.channel();
+        let seq = match channel {
+            Channel::Chat => 0,
+            Channel::Control | Channel::Moves => self.outbox.next_seq(),
+        };
+        let envelope = Envelope {
+            session: self.session.clone(),
+            game: self.game_number,
+            seq,
+            message,
+        };
 
-        if let Err(e) = sender.send(Recipients::All, message.into(), false).await {
+        // The message is kept until it's acknowledged, so that it's retransmitted if it's lost.
+        let bytes = bytes::Bytes::from(envelope);
+        if channel != Channel::Chat {
+            let now = self.context.as_present().current();
+            self.outbox.track(seq, channel, bytes.clone(), now);
+        }
+
+        let recipients = Recipients::One(self.opponent.clone());
+        if let Err(e) = sender.send(channel, recipients, bytes, false).await {
             Err(e).wrap_err("failed to send message")
         } else {
             Ok(())
         }
     }
 
-    /// Prompts the configured LLM for the next move.
-    ///
-    /// Constructs a prompt containing the game grid size and all previously played moves,
-    /// then sends it to the LLM model. The model's response is parsed to extract a coordinate
-    /// in the format "A1", "B2", etc., which is then converted to (x, y) coordinates.
-    ///
-    /// Returns the (x, y) coordinates of the suggested move, or a default coordinate (1, 1)
-    /// if parsing fails.
-    async fn prompt_for_next_move(&mut self) -> eyre::Result<(u8, u8)> {
-        let played_moves = self
-            .moves
-            .iter()
-            .map(|m| m.get_position())
-            .collect::<Vec<String>>()
-            .join(",");
-
-        let prompt = format!(
-            r#"
-            You're playing a game of battleship on a {}x{} grid.
-            You're supposed to identify the next move that's reasonable for you to win this game.
-            DO NOT create any code.
-            You MUST purely provide a tactically sensible move as the output of this prompt.
-            I am going to provide the list of past moves to you and you need to decide on the next move to play.
-            If no previous moves have been played, just attack a random field in the grid.
-            The past moves have been the following: ({}).
-            You MUST ONLY return the next field in the form of e.g. 'A1', 'B2', etc. and nothing else!!
-            This output will be parsed so it's mandatory to NOT INCLUDE ANYTHING EXCEPT THE COORDINATE!!!
-            (no comments, no formatting, NOTHING)
-            "#,
-            self.game.grid.width, self.game.grid.height, played_moves,
-        );
-
-        let output = self.model.prompt(&prompt)?;
-        self.log(LogType::Debug, &format!("got LLM result: {}", output))
-            .await?;
-
-        let parsed = match Regex::new("[A-Z][0-9]").unwrap().find(output.trim()) {
-            Some(m) => m.as_str(),
-            None => return Ok((1, 1)), // TODO: this returns a default right now, maybe do something else here?
-        };
+    /// Retransmits the messages that were not acknowledged in time.
+    async fn retransmit(
+        &mut self,
+        mut sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+    ) -> eyre::Result<()> {
+        let now = self.context.as_present().current();
+        for (seq, channel, bytes) in self.outbox.due(now) {
+            self.log(
+                LogType::Debug,
+                &format!("retransmitting unacknowledged message {}", seq),
+            )
+            .await;
 
-        let mut coord = Coordinate::default();
-        match Coordinate::try_from(parsed.to_string()) {
-            Ok(c) => coord = c,
-            Err(_) => {
-                self.end_game_with_log(
-                    LogType::Error,
-                    &format!("failed to parse coordinate from llm output: {}", output),
-                )
-                .await
+            let recipients = Recipients::One(self.opponent.clone());
+            if let Err(e) = sender.send(channel, recipients, bytes, false).await {
+                return Err(e).wrap_err("failed to retransmit message");
             }
         }
 
-        Ok((coord.x, coord.y))
+        Ok(())
     }
 
     /// Update the opponent's grid with a new attack.
@@ -454,13 +1362,22 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
             return Err(eyre::eyre!("invalid move: {:?}", mv));
         }
 
-        let length = self.moves.len() - 1;
-        self.moves
-            .get_mut(length)
-            .expect("failed to get last move")
-            .is_hit = is_hit;
+        // The result must answer the player's last attack.
+        let Some(last) = self
+            .moves
+            .last_mut()
+            .filter(|last| last.get_number() == mv.get_number())
+        else {
+            return Err(eyre::eyre!("received result for unknown move: {:?}", mv));
+        };
+        last.is_hit = is_hit;
 
         self.game.attack(mv.get_x(), mv.get_y(), is_hit)?;
+        self.publish(Event::result(
+            Side::Own,
+            &Move::new(mv.get_number(), mv.get_x(), mv.get_y(), is_hit),
+        ))
+        .await;
         self.draw_grid().await?;
 
         match is_hit {
@@ -479,5 +1396,473 @@ impl<R: Rng + CryptoRng + Spawner, C: Signer> GameStateActor<R, C> {
                 .await
             }
         }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commonware_cryptography::{PrivateKeyExt as _, ed25519::PrivateKey};
+    use commonware_p2p::simulated::{self, Link, Network};
+    use commonware_runtime::{Metrics, Runner, deterministic};
+    use futures::FutureExt;
+
+    /// Plays a game between two actors on the simulated network and returns
+    /// the player with the first turn and the winner, as seen by both players.
+    ///
+    /// The second player only connects after the given delay, so that the first
+    /// ready messages of the first player are lost.
+    /// If enabled, an intruder keeps sending attacks of another session to both players,
+    /// while it must not receive any messages of the players.
+    /// The links between the players deliver the messages with the given success rate.
+    fn play(
+        seed: u64,
+        delay: Duration,
+        latency: Duration,
+        intruder: bool,
+        success_rate: f64,
+    ) -> [[Side; 2]; 2] {
+        deterministic::Runner::seeded(seed).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+
+            let signers = [PrivateKey::from_seed(0), PrivateKey::from_seed(1)];
+            for (from, to) in [(0, 1), (1, 0)] {
+                oracle
+                    .add_link(
+                        signers[from].public_key(),
+                        signers[to].public_key(),
+                        Link {
+                            latency,
+                            jitter: Duration::ZERO,
+                            success_rate,
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            let mut intruder_receiver = None;
+            if intruder {
+                let signer = PrivateKey::from_seed(2);
+                for player in &signers {
+                    for (from, to) in [
+                        (signer.public_key(), player.public_key()),
+                        (player.public_key(), signer.public_key()),
+                    ] {
+                        oracle
+                            .add_link(
+                                from,
+                                to,
+                                Link {
+                                    latency,
+                                    jitter: Duration::ZERO,
+                                    success_rate: 1.0,
+                                },
+                            )
+                            .await
+                            .unwrap();
+                    }
+                }
+
+                let (mut sender, receiver) = oracle
+                    .control(signer.public_key())
+                    .register(0)
+                    .await
+                    .unwrap();
+                intruder_receiver = Some(receiver);
+                context.with_label("intruder").spawn(|context| async move {
+                    loop {
+                        let envelope = Envelope {
+                            session: Some("stale".into()),
+                            game: 1,
+                            seq: 1,
+                            message: Message::Attack {
+                                m: Move::new(1, 1, 1, false),
+                            },
+                        };
+                        let _ = sender.send(Recipients::All, envelope.into(), false).await;
+                        context.sleep(Duration::from_secs(1)).await;
+                    }
+                });
+            }
+
+            let opponents = [signers[1].public_key(), signers[0].public_key()];
+            let mut receivers = Vec::new();
+            for (id, (signer, opponent)) in signers.into_iter().zip(opponents).enumerate() {
+                if id == 1 {
+                    context.sleep(delay).await;
+                }
+
+                let (sender, receiver) = oracle
+                    .control(signer.public_key())
+                    .register(0)
+                    .await
+                    .unwrap();
+                let (events_sender, events_receiver) = mpsc::channel(1024);
+                let (actor, _) = GameStateActor::new(
+                    context.with_label(&format!("player_{}", id)),
+                    EventSink::new(vec![events_sender]),
+                    LogLevel::Info,
+                    signer,
+                    opponent,
+                    Box::new(HuntTarget::new()),
+                );
+                actor.start(ChannelSender::single(sender), receiver);
+
+                receivers.push(events_receiver.map(move |event| (id, event)));
+            }
+
+            let mut events = stream::select_all(receivers);
+            let (mut firsts, mut winners) = ([None; 2], [None; 2]);
+            while winners.iter().any(Option::is_none) {
+                let (id, event) = events.next().await.expect("actor stopped");
+                match event {
+                    Event::Start { first } => firsts[id] = Some(first),
+                    Event::GameOver { summary } => winners[id] = Some(summary.winner),
+                    Event::Error { message } => panic!("player {} failed: {}", id, message),
+                    _ => (),
+                }
+            }
+
+            // The messages are only sent to the opponent, so the other peers don't see the moves.
+            if let Some(mut receiver) = intruder_receiver {
+                assert!(
+                    receiver.recv().now_or_never().is_none(),
+                    "intruder received a message of the players"
+                );
+            }
+
+            [
+                firsts.map(|first| first.expect("game did not start")),
+                winners.map(|winner| winner.unwrap()),
+            ]
+        })
+    }
+
+    #[test]
+    fn test_simultaneous_start() {
+        // NOTE: with a latency of the tick interval, the ready messages of both players cross each other.
+        for latency in [Duration::from_millis(10), TICK_INTERVAL] {
+            for seed in 0..5 {
+                let [firsts, winners] = play(seed, Duration::ZERO, latency, false, 1.0);
+
+                // both players agree on the first turn and the winner
+                assert_ne!(firsts[0], firsts[1]);
+                assert_ne!(winners[0], winners[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_delayed_start() {
+        for delay in [
+            Duration::from_secs(1),
+            TICK_INTERVAL,
+            Duration::from_secs(9),
+        ] {
+            let [firsts, winners] = play(0, delay, Duration::from_millis(10), false, 1.0);
+
+            assert_ne!(firsts[0], firsts[1]);
+            assert_ne!(winners[0], winners[1]);
+        }
+    }
+
+    #[test]
+    fn test_foreign_session() {
+        // the attacks of the other peer are ignored, so the game is played as usual
+        let [firsts, winners] = play(0, Duration::ZERO, Duration::from_millis(10), true, 1.0);
+
+        assert_ne!(firsts[0], firsts[1]);
+        assert_ne!(winners[0], winners[1]);
+    }
+
+    #[test]
+    fn test_stale_messages() {
+        // the opponent's messages of another session or game are dropped, even though they're delivered
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+            let (sender, _) = oracle
+                .control(PrivateKey::from_seed(0).public_key())
+                .register(0)
+                .await
+                .unwrap();
+            let sender = ChannelSender::single(sender);
+
+            let opponent = PrivateKey::from_seed(1).public_key();
+            let (mut actor, _) = GameStateActor::new(
+                context.with_label("player"),
+                EventSink::default(),
+                LogLevel::Info,
+                PrivateKey::from_seed(0),
+                opponent.clone(),
+                Box::new(HuntTarget::new()),
+            );
+            actor.session = Some("session".into());
+            actor.state = State::Playing;
+
+            for (seq, session, game) in [(1, "stale", 1), (2, "session", 2), (3, "session", 1)] {
+                let envelope = Envelope {
+                    session: Some(session.into()),
+                    game,
+                    seq,
+                    message: Message::Attack {
+                        m: Move::new(1, 1, 1, false),
+                    },
+                };
+                actor
+                    .receive(sender.clone(), opponent.clone(), envelope.into())
+                    .await
+                    .unwrap();
+            }
+
+            // only the attack of the current session and game was handled
+            assert_eq!(1, actor.opponent_moves.len());
+        });
+    }
+
+    #[test]
+    fn test_held_back_moves() {
+        // a move that overtook a control message is acknowledged, but only handled once the control message arrived
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+
+            let signers = [PrivateKey::from_seed(0), PrivateKey::from_seed(1)];
+            oracle
+                .add_link(
+                    signers[0].public_key(),
+                    signers[1].public_key(),
+                    Link {
+                        latency: Duration::from_millis(10),
+                        jitter: Duration::ZERO,
+                        success_rate: 1.0,
+                    },
+                )
+                .await
+                .unwrap();
+            let (sender, _) = oracle
+                .control(signers[0].public_key())
+                .register(0)
+                .await
+                .unwrap();
+            let sender = ChannelSender::single(sender);
+            let (_, mut opponent_receiver) = oracle
+                .control(signers[1].public_key())
+                .register(0)
+                .await
+                .unwrap();
+
+            let opponent = signers[1].public_key();
+            let (mut actor, _) = GameStateActor::new(
+                context.with_label("player"),
+                EventSink::default(),
+                LogLevel::Info,
+                PrivateKey::from_seed(0),
+                opponent.clone(),
+                Box::new(HuntTarget::new()),
+            );
+            actor.session = Some("session".into());
+            actor.state = State::Placing;
+            actor.first = Some(Side::Opponent);
+
+            let envelope = |seq, message| Envelope {
+                session: Some("session".into()),
+                game: 1,
+                seq,
+                message,
+            };
+            let attack = Message::Attack {
+                m: Move::new(1, 1, 1, false),
+            };
+
+            actor
+                .receive(sender.clone(), opponent.clone(), envelope(2, attack).into())
+                .await
+                .unwrap();
+            assert!(actor.opponent_moves.is_empty());
+
+            let (_, ack) = opponent_receiver.recv().await.unwrap();
+            assert!(matches!(
+                Envelope::try_from(ack).unwrap().message,
+                Message::Ack { seq: 2 }
+            ));
+
+            actor
+                .receive(
+                    sender.clone(),
+                    opponent.clone(),
+                    envelope(1, Message::Placed).into(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(State::Playing, actor.state);
+            assert_eq!(1, actor.opponent_moves.len());
+        });
+    }
+
+    #[test]
+    fn test_lossy_link() {
+        // the lost messages and acknowledgements are retransmitted, so the game is played as usual
+        for seed in 0..3 {
+            let [firsts, winners] =
+                play(seed, Duration::ZERO, Duration::from_millis(10), false, 0.6);
+
+            assert_ne!(firsts[0], firsts[1]);
+            assert_ne!(winners[0], winners[1]);
+        }
+    }
+
+    #[test]
+    fn test_reveal() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let reveal = |player: &game::Player| {
+                player
+                    .grid
+                    .ships
+                    .iter()
+                    .map(|ship| (ship.type_, ship.coords.iter().map(|c| (c.x, c.y)).collect()))
+                    .collect::<Vec<_>>()
+            };
+            let new_actor = |label: &str| {
+                let (mut actor, _) = GameStateActor::new(
+                    context.with_label(label),
+                    EventSink::default(),
+                    LogLevel::Info,
+                    PrivateKey::from_seed(0),
+                    PrivateKey::from_seed(1).public_key(),
+                    Box::new(HuntTarget::new()),
+                );
+                actor.winner = Some(Side::Own);
+                actor
+            };
+
+            // a standard fleet without any attacks is accepted
+            let ships = reveal(&game::Player::new());
+            let mut actor = new_actor("truthful");
+            actor.handle_reveal(ships).await.unwrap();
+            assert!(!actor.summary.unwrap().disputed);
+
+            // an empty fleet is rejected, but still ends the game
+            let mut actor = new_actor("empty");
+            actor.handle_reveal(Vec::new()).await.unwrap();
+            assert!(actor.summary.unwrap().disputed);
+
+            // the fleet must match the results of the attacks
+            let opponent = game::Player::new();
+            let ship = &opponent.grid.ships[0].coords[0];
+            let mut actor = new_actor("contradicting");
+            actor.moves.push(Move::new(1, ship.x, ship.y, false));
+            actor.handle_reveal(reveal(&opponent)).await.unwrap();
+            assert!(actor.summary.unwrap().disputed);
+        });
+    }
+
+    #[test]
+    fn test_strategy_timeout() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+            let (sender, _) = oracle
+                .control(PrivateKey::from_seed(0).public_key())
+                .register(0)
+                .await
+                .unwrap();
+            let sender = ChannelSender::single(sender);
+
+            let (mut actor, _) = GameStateActor::new(
+                context.with_label("player"),
+                EventSink::default(),
+                LogLevel::Info,
+                PrivateKey::from_seed(0),
+                PrivateKey::from_seed(1).public_key(),
+                Box::new(HuntTarget::new()),
+            );
+            actor.session = Some("session".into());
+            actor.state = State::Playing;
+            actor.my_turn = true;
+            let (results_sender, mut results) = mpsc::channel(1);
+
+            // the computation is started, but its result is not handled within the turn timeout
+            actor
+                .attack(sender.clone(), results_sender.clone())
+                .await
+                .unwrap();
+            assert!(actor.pending.is_some() && actor.strategy.is_none());
+
+            context.sleep(TURN_TIMEOUT).await;
+            actor
+                .attack(sender.clone(), results_sender.clone())
+                .await
+                .unwrap();
+            assert!(actor.pending.is_none() && !actor.my_turn);
+            assert_eq!(1, actor.moves.len());
+
+            // while the strategy did not return, the fallback strategy plays right away
+            actor.my_turn = true;
+            actor
+                .attack(sender.clone(), results_sender.clone())
+                .await
+                .unwrap();
+            assert!(actor.pending.is_none() && !actor.my_turn);
+            assert_eq!(2, actor.moves.len());
+
+            // the outdated result is discarded, but the strategy is handed back
+            let (number, result, strategy) = results.next().await.unwrap();
+            assert_eq!(1, number);
+            actor
+                .handle_strategy_result(sender.clone(), number, result, strategy)
+                .await
+                .unwrap();
+            assert_eq!(2, actor.moves.len());
+            assert!(actor.strategy.is_some());
+
+            // the next move is computed by the strategy again
+            actor.my_turn = true;
+            actor
+                .attack(sender.clone(), results_sender.clone())
+                .await
+                .unwrap();
+            let (number, result, strategy) = results.next().await.unwrap();
+            assert_eq!(3, number);
+            actor
+                .handle_strategy_result(sender.clone(), number, result, strategy)
+                .await
+                .unwrap();
+            assert_eq!(3, actor.moves.len());
+            assert!(actor.pending.is_none() && actor.strategy.is_some());
+        });
     }
 }
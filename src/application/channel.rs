@@ -0,0 +1,167 @@
+use bytes::Bytes;
+use commonware_p2p::{Recipients, Sender};
+
+/// The channels of the p2p network, over which the messages of the games are sent.
+///
+/// Every channel has its own quota and backlog, so that e.g. a flood of moves
+/// can't delay the session control messages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Channel {
+    /// The session control messages, i.e. the handshake, the reveal and the rematch.
+    Control,
+    /// The moves of the game.
+    Moves,
+    /// The chat between the players.
+    Chat,
+}
+
+/// Sends every message of a game over the channel it belongs to.
+#[derive(Clone, Debug)]
+pub struct ChannelSender<S: Sender> {
+    control: S,
+    moves: S,
+    chat: S,
+}
+
+impl<S: Sender> ChannelSender<S> {
+    pub fn new(control: S, moves: S, chat: S) -> Self {
+        Self {
+            control,
+            moves,
+            chat,
+        }
+    }
+
+    /// Sends the messages of all channels with the given sender, e.g. in the selfplay.
+    pub fn single(sender: S) -> Self {
+        Self::new(sender.clone(), sender.clone(), sender)
+    }
+
+    /// Sends the encoded message over the given channel.
+    ///
+    /// NOTE: the channel is passed by the caller, which knows the message before encoding it
+    /// (see [`Message::channel`](super::ingress::Message::channel)).
+    pub async fn send(
+        &mut self,
+        channel: Channel,
+        recipients: Recipients<S::PublicKey>,
+        message: Bytes,
+        priority: bool,
+    ) -> Result<Vec<S::PublicKey>, S::Error> {
+        let sender = match channel {
+            Channel::Control => &mut self.control,
+            Channel::Moves => &mut self.moves,
+            Channel::Chat => &mut self.chat,
+        };
+
+        sender.send(recipients, message, priority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use commonware_cryptography::{PrivateKeyExt as _, Signer, ed25519::PrivateKey};
+    use commonware_p2p::{
+        Receiver,
+        simulated::{self, Link, Network},
+    };
+    use commonware_runtime::{Metrics, Runner, deterministic};
+
+    use crate::{
+        application::{
+            Move,
+            ingress::{Envelope, Message},
+        },
+        config::{CHAT_CHANNEL, CONTROL_CHANNEL, MOVES_CHANNEL},
+    };
+
+    #[test]
+    fn test_channel_sender() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+
+            let [first, second] = [0, 1].map(|seed| PrivateKey::from_seed(seed).public_key());
+            oracle
+                .add_link(
+                    first.clone(),
+                    second.clone(),
+                    Link {
+                        latency: Duration::from_millis(10),
+                        jitter: Duration::ZERO,
+                        success_rate: 1.0,
+                    },
+                )
+                .await
+                .unwrap();
+
+            let mut channels = [Vec::new(), Vec::new()];
+            for (channels, public_key) in channels.iter_mut().zip([&first, &second]) {
+                let mut control = oracle.control(public_key.clone());
+                for channel in [CONTROL_CHANNEL, MOVES_CHANNEL, CHAT_CHANNEL] {
+                    channels.push(control.register(channel).await.unwrap());
+                }
+            }
+            let [senders, receivers] = channels;
+            let mut senders = senders.into_iter().map(|(sender, _)| sender);
+            let mut sender = ChannelSender::new(
+                senders.next().unwrap(),
+                senders.next().unwrap(),
+                senders.next().unwrap(),
+            );
+            let mut receivers = receivers
+                .into_iter()
+                .map(|(_, receiver)| receiver)
+                .collect::<Vec<_>>();
+
+            // every message is only received over its own channel
+            for (message, index) in [
+                (Message::Placed, 0),
+                (
+                    Message::Attack {
+                        m: Move::new(1, 1, 1, false),
+                    },
+                    1,
+                ),
+            ] {
+                let channel = message.channel();
+                let envelope = Envelope {
+                    session: None,
+                    game: 1,
+                    seq: 1,
+                    message,
+                };
+                sender
+                    .send(
+                        channel,
+                        Recipients::One(second.clone()),
+                        envelope.into(),
+                        false,
+                    )
+                    .await
+                    .unwrap();
+
+                let (peer, received) = receivers[index].recv().await.unwrap();
+                assert_eq!(first, peer);
+                assert_eq!(
+                    index == 1,
+                    matches!(
+                        Envelope::try_from(received).unwrap().message,
+                        Message::Attack { .. }
+                    )
+                );
+            }
+        });
+    }
+}
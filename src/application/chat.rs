@@ -0,0 +1,116 @@
+//! Short text messages between the players during a game.
+//!
+//! The messages are limited in their length and rate, both when sending them
+//! and when receiving them, so that a chatty (or malicious) opponent can't flood the TUI.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
+
+/// The maximum number of characters of a chat message.
+pub const MAX_CHAT_LENGTH: usize = 200;
+
+/// The number of chat messages that may be sent within the [`CHAT_WINDOW`].
+///
+/// NOTE: the chat messages are not retransmitted, so the rate of the chat channel must accept
+/// a burst of this many messages (see [`ChannelPreset`](crate::config::ChannelPreset)).
+pub const CHAT_LIMIT: usize = 5;
+
+/// The sliding window over which the chat messages are rate limited.
+const CHAT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Validates the text of a chat message.
+pub fn validate(text: &str) -> eyre::Result<()> {
+    if text.trim().is_empty() {
+        return Err(eyre::eyre!("chat message is empty"));
+    }
+    if text.chars().count() > MAX_CHAT_LENGTH {
+        return Err(eyre::eyre!(
+            "chat message exceeds {} characters",
+            MAX_CHAT_LENGTH
+        ));
+    }
+    // NOTE: control characters (e.g. escape sequences) could mess up the terminal.
+    if text.chars().any(char::is_control) {
+        return Err(eyre::eyre!("chat message contains control characters"));
+    }
+
+    Ok(())
+}
+
+/// Limits the number of chat messages within a sliding window.
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    /// The times of the messages within the current window, oldest first.
+    times: VecDeque<SystemTime>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(CHAT_LIMIT, CHAT_WINDOW)
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            times: VecDeque::with_capacity(limit),
+        }
+    }
+
+    /// Records a message at the given time, returning false if it exceeds the limit.
+    ///
+    /// NOTE: rejected messages are not recorded, so they don't extend the window.
+    pub fn check(&mut self, now: SystemTime) -> bool {
+        while let Some(oldest) = self.times.front()
+            && now.duration_since(*oldest).unwrap_or_default() >= self.window
+        {
+            self.times.pop_front();
+        }
+
+        if self.times.len() >= self.limit {
+            return false;
+        }
+
+        self.times.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("good luck!").is_ok());
+        assert!(validate(&"🚢".repeat(MAX_CHAT_LENGTH)).is_ok());
+
+        assert!(validate("").is_err());
+        assert!(validate("   ").is_err());
+        assert!(validate(&"a".repeat(MAX_CHAT_LENGTH + 1)).is_err());
+        assert!(validate("\x1b[2Jgg").is_err());
+        assert!(validate("line\nbreak").is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(2, Duration::from_secs(10));
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert!(limiter.check(at(0)));
+        assert!(limiter.check(at(1)));
+        assert!(!limiter.check(at(2)));
+
+        // the rejected message does not extend the window
+        assert!(limiter.check(at(10)));
+        assert!(!limiter.check(at(10)));
+        assert!(limiter.check(at(11)));
+    }
+}
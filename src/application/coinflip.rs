@@ -0,0 +1,138 @@
+//! Commit-reveal coin flip to fairly pick the player with the first turn.
+//!
+//! Both players commit to a random nonce by sending its hash during the handshake.
+//! Once both commitments are exchanged, the nonces are revealed and verified,
+//! and the first player is derived from the hash of both nonces.
+//! Since neither player knows the other's nonce before committing to their own,
+//! neither player can bias the outcome.
+
+use commonware_cryptography::{Hasher, Sha256};
+use commonware_utils::{from_hex, hex};
+use rand::Rng;
+use serde::Serialize;
+
+use crate::events::Side;
+
+/// The local player's part of the coin flip.
+pub struct CoinFlip {
+    nonce: [u8; 32],
+    commitment: String,
+}
+
+/// The verifiable outcome of a coin flip, which is recorded in the transcript.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Flip {
+    pub own_commitment: String,
+    pub own_nonce: String,
+    pub opponent_commitment: String,
+    pub opponent_nonce: String,
+    /// The player with the first turn.
+    pub first: Side,
+}
+
+impl CoinFlip {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let nonce = rng.r#gen::<[u8; 32]>();
+
+        Self {
+            nonce,
+            commitment: commit(&nonce),
+        }
+    }
+
+    /// Returns the hex-encoded commitment, which is sent to the opponent during the handshake.
+    pub fn commitment(&self) -> &str {
+        &self.commitment
+    }
+
+    /// Returns the hex-encoded nonce, which is revealed once both commitments were exchanged.
+    pub fn nonce(&self) -> String {
+        hex(&self.nonce)
+    }
+
+    /// Verifies the opponent's revealed nonce against their commitment and resolves the coin flip.
+    ///
+    /// NOTE: the player with the lower commitment has the first turn if the lowest bit
+    /// of the combined hash is zero, so both players derive the same outcome.
+    pub fn resolve(&self, opponent_commitment: &str, opponent_nonce: &str) -> eyre::Result<Flip> {
+        if opponent_commitment == self.commitment {
+            return Err(eyre::eyre!("opponent reused own commitment"));
+        }
+
+        let nonce = from_hex(opponent_nonce).ok_or_else(|| eyre::eyre!("invalid nonce"))?;
+        if commit(&nonce) != opponent_commitment {
+            return Err(eyre::eyre!("opponent nonce does not match commitment"));
+        }
+
+        let own_lower = self.commitment.as_str() < opponent_commitment;
+        let (lower, higher) = match own_lower {
+            true => (self.nonce.as_slice(), nonce.as_slice()),
+            false => (nonce.as_slice(), self.nonce.as_slice()),
+        };
+
+        let mut hasher = Sha256::new();
+        hasher.update(lower);
+        hasher.update(higher);
+        let lower_first = hasher.finalize().as_ref()[0] & 1 == 0;
+
+        Ok(Flip {
+            own_commitment: self.commitment.clone(),
+            own_nonce: self.nonce(),
+            opponent_commitment: opponent_commitment.to_string(),
+            opponent_nonce: opponent_nonce.to_string(),
+            first: match own_lower == lower_first {
+                true => Side::Own,
+                false => Side::Opponent,
+            },
+        })
+    }
+}
+
+/// Returns the hex-encoded hash of the given nonce.
+fn commit(nonce: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(nonce);
+
+    hex(hasher.finalize().as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn test_resolve() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut firsts = [0; 2];
+
+        for _ in 0..100 {
+            let (a, b) = (CoinFlip::new(&mut rng), CoinFlip::new(&mut rng));
+
+            let flip_a = a.resolve(b.commitment(), &b.nonce()).unwrap();
+            let flip_b = b.resolve(a.commitment(), &a.nonce()).unwrap();
+
+            // both players agree on the outcome
+            assert_ne!(flip_a.first, flip_b.first);
+            firsts[(flip_a.first == Side::Own) as usize] += 1;
+        }
+
+        // the outcome is not fixed to one of the players
+        assert!(firsts[0] > 0 && firsts[1] > 0);
+    }
+
+    #[test]
+    fn test_resolve_invalid() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (a, b, c) = (
+            CoinFlip::new(&mut rng),
+            CoinFlip::new(&mut rng),
+            CoinFlip::new(&mut rng),
+        );
+
+        assert!(a.resolve(b.commitment(), &c.nonce()).is_err());
+        assert!(a.resolve(b.commitment(), "not hex").is_err());
+        assert!(a.resolve(a.commitment(), &a.nonce()).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Reliable delivery of the messages between the players.
+//!
+//! The p2p network drops messages silently, e.g. if the opponent is not connected yet
+//! or a link is lossy, which would deadlock a game that's waiting for an attack or its result.
+//! Every message is therefore sent with a sequence number, which the receiver acknowledges:
+//!
+//! - The [`Outbox`] keeps the sent messages until they're acknowledged and retransmits them
+//!   with an exponential backoff.
+//! - The [`Inbox`] hands the received messages over in the order of their sequence numbers,
+//!   so that retransmitted messages can't overtake each other, and drops duplicates.
+//!
+//! The acknowledgements themselves are not sequenced, since a lost acknowledgement only causes
+//! a retransmission, which is acknowledged again.
+//!
+//! NOTE: the chat messages are not sequenced either, but sent best-effort. Otherwise a lost
+//! or throttled chat message would hold back every later move until it's retransmitted.
+//!
+//! The control and the moves channel share a single sequence, so a lost control message
+//! holds back the later moves until it's retransmitted (i.e. head-of-line blocking).
+//! This is on purpose, since the messages are only valid in their order across both channels,
+//! e.g. the opponent must have placed its ships before its first attack, and the game must be over
+//! before the ships are revealed. The held back moves are still acknowledged, so that only
+//! the lost message is retransmitted.
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+
+use super::{Channel, ingress::Envelope};
+
+/// The duration after which an unacknowledged message is retransmitted for the first time.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The maximum duration between two retransmissions of the same message.
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// The number of sequence numbers ahead of the next expected message that are buffered.
+///
+/// NOTE: this bounds the memory a peer can take up by skipping sequence numbers.
+const RECEIVE_WINDOW: u64 = 64;
+
+/// A sent message that was not acknowledged yet.
+struct Pending {
+    /// The channel over which the message is retransmitted.
+    channel: Channel,
+    /// The encoded envelope, which is retransmitted as is.
+    bytes: Bytes,
+    /// The time at which the message is retransmitted next.
+    due: SystemTime,
+    /// The duration until the next retransmission.
+    backoff: Duration,
+}
+
+/// Keeps the sent messages until they're acknowledged.
+pub struct Outbox {
+    /// The sequence number of the next sent message, starting at 1.
+    next: u64,
+    pending: BTreeMap<u64, Pending>,
+}
+
+impl Default for Outbox {
+    fn default() -> Self {
+        Self {
+            next: 1,
+            pending: BTreeMap::new(),
+        }
+    }
+}
+
+impl Outbox {
+    /// Returns the sequence number of the next sent message.
+    pub fn next_seq(&mut self) -> u64 {
+        let seq = self.next;
+        self.next += 1;
+
+        seq
+    }
+
+    /// Keeps the encoded envelope with the given sequence number until it's acknowledged.
+    pub fn track(&mut self, seq: u64, channel: Channel, bytes: Bytes, now: SystemTime) {
+        self.pending.insert(
+            seq,
+            Pending {
+                channel,
+                bytes,
+                due: now + INITIAL_BACKOFF,
+                backoff: INITIAL_BACKOFF,
+            },
+        );
+    }
+
+    /// Marks the message with the given sequence number as delivered,
+    /// returning false if it was not pending (e.g. for a duplicate acknowledgement).
+    pub fn ack(&mut self, seq: u64) -> bool {
+        self.pending.remove(&seq).is_some()
+    }
+
+    /// Returns the messages that are due for a retransmission, doubling their backoff.
+    pub fn due(&mut self, now: SystemTime) -> Vec<(u64, Channel, Bytes)> {
+        self.pending
+            .iter_mut()
+            .filter(|(_, pending)| pending.due <= now)
+            .map(|(seq, pending)| {
+                pending.backoff = (pending.backoff * 2).min(MAX_BACKOFF);
+                pending.due = now + pending.backoff;
+
+                (*seq, pending.channel, pending.bytes.clone())
+            })
+            .collect()
+    }
+
+    /// Returns the time of the next retransmission, if any message is pending.
+    pub fn deadline(&self) -> Option<SystemTime> {
+        self.pending.values().map(|pending| pending.due).min()
+    }
+}
+
+/// Hands the received messages over in order, dropping duplicates.
+pub struct Inbox {
+    /// The sequence number of the next message to hand over, starting at 1.
+    next: u64,
+    /// The messages that arrived ahead of a missing message.
+    buffered: BTreeMap<u64, Envelope>,
+}
+
+impl Default for Inbox {
+    fn default() -> Self {
+        Self {
+            next: 1,
+            buffered: BTreeMap::new(),
+        }
+    }
+}
+
+impl Inbox {
+    /// Receives the given envelope, returning the envelopes that can be handled in order.
+    ///
+    /// Duplicates yield no envelopes, but must still be acknowledged, since the previous
+    /// acknowledgement might have been lost. Envelopes beyond the receive window are
+    /// dropped without an acknowledgement (`None`), so that they're retransmitted later.
+    pub fn receive(&mut self, envelope: Envelope) -> Option<Vec<Envelope>> {
+        let seq = envelope.seq;
+        if seq >= self.next + RECEIVE_WINDOW {
+            return None;
+        }
+        if seq < self.next || self.buffered.contains_key(&seq) {
+            return Some(Vec::new());
+        }
+
+        self.buffered.insert(seq, envelope);
+
+        let mut envelopes = Vec::new();
+        while let Some(envelope) = self.buffered.remove(&self.next) {
+            envelopes.push(envelope);
+            self.next += 1;
+        }
+
+        Some(envelopes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::UNIX_EPOCH;
+
+    use crate::application::ingress::Message;
+
+    fn envelope(seq: u64) -> Envelope {
+        Envelope {
+            session: None,
+            game: 1,
+            seq,
+            message: Message::Placed,
+        }
+    }
+
+    #[test]
+    fn test_outbox() {
+        let mut outbox = Outbox::default();
+        let at = |millis| UNIX_EPOCH + Duration::from_millis(millis);
+
+        for _ in 0..2 {
+            let seq = outbox.next_seq();
+            outbox.track(seq, Channel::Moves, Bytes::from(seq.to_string()), at(0));
+        }
+        assert!(outbox.due(at(999)).is_empty());
+        assert_eq!(Some(at(1_000)), outbox.deadline());
+
+        // the acknowledged messages are not retransmitted
+        assert!(outbox.ack(1));
+        assert!(!outbox.ack(1));
+        assert_eq!(
+            vec![(2, Channel::Moves, Bytes::from("2"))],
+            outbox.due(at(1_000))
+        );
+
+        // the backoff is doubled up to its maximum
+        assert_eq!(Some(at(3_000)), outbox.deadline());
+        let mut now = 3_000;
+        for _ in 0..5 {
+            assert_eq!(1, outbox.due(at(now)).len());
+            now = outbox
+                .deadline()
+                .unwrap()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64;
+        }
+        assert_eq!(at(3_000 + 4_000 + 8_000 * 4), at(now));
+
+        assert!(outbox.ack(2));
+        assert_eq!(None, outbox.deadline());
+    }
+
+    #[test]
+    fn test_inbox() {
+        let mut inbox = Inbox::default();
+        let seqs = |envelopes: Option<Vec<Envelope>>| {
+            envelopes.map(|envelopes| envelopes.iter().map(|e| e.seq).collect::<Vec<u64>>())
+        };
+
+        assert_eq!(Some(vec![1]), seqs(inbox.receive(envelope(1))));
+
+        // the messages ahead of a missing message are buffered until it arrives
+        assert_eq!(Some(vec![]), seqs(inbox.receive(envelope(3))));
+        assert_eq!(Some(vec![]), seqs(inbox.receive(envelope(4))));
+        assert_eq!(Some(vec![2, 3, 4]), seqs(inbox.receive(envelope(2))));
+
+        // duplicates are dropped, whether they were handled or are still buffered
+        assert_eq!(Some(vec![]), seqs(inbox.receive(envelope(2))));
+        assert_eq!(Some(vec![]), seqs(inbox.receive(envelope(6))));
+        assert_eq!(Some(vec![]), seqs(inbox.receive(envelope(6))));
+        assert_eq!(Some(vec![]), seqs(inbox.receive(envelope(0))));
+
+        // the messages beyond the receive window are dropped
+        assert_eq!(None, seqs(inbox.receive(envelope(5 + RECEIVE_WINDOW))));
+        assert_eq!(Some(vec![5, 6]), seqs(inbox.receive(envelope(5))));
+    }
+}
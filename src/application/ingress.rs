@@ -1,8 +1,48 @@
-use super::gamestate;
+use super::{Channel, chat, gamestate, session::SESSION_ID_LENGTH};
+use crate::game::{self, GRID_SIZE, Orientation, ShipType};
 
 use eyre;
+use futures::channel::mpsc::{Sender, TrySendError};
 use serde::{Deserialize, Serialize};
 
+/// The commands that can be sent to the game state actor, e.g. by the GUI.
+pub enum Command {
+    /// Saves the transcript of the finished game.
+    SaveTranscript,
+    /// Requests a rematch with the same opponent.
+    Rematch,
+    /// Sends a chat message to the opponent.
+    Chat(String),
+}
+
+/// The mailbox to send commands to the game state actor.
+///
+/// NOTE: the commands are sent without waiting, since the game state actor might be blocked
+/// on publishing its events to the sender of the command (e.g. the GUI). A command is rejected
+/// if the mailbox is full.
+#[derive(Clone)]
+pub struct Mailbox {
+    sender: Sender<Command>,
+}
+
+impl Mailbox {
+    pub fn new(sender: Sender<Command>) -> Self {
+        Self { sender }
+    }
+
+    pub fn save_transcript(&mut self) -> Result<(), TrySendError<Command>> {
+        self.sender.try_send(Command::SaveTranscript)
+    }
+
+    pub fn rematch(&mut self) -> Result<(), TrySendError<Command>> {
+        self.sender.try_send(Command::Rematch)
+    }
+
+    pub fn chat(&mut self, text: String) -> Result<(), TrySendError<Command>> {
+        self.sender.try_send(Command::Chat(text))
+    }
+}
+
 /// Message describes the available messages to be sent between
 /// the participants.
 #[derive(Debug, Deserialize, Serialize)]
@@ -16,11 +56,44 @@ pub enum Message {
     Hit { m: gamestate::Move },
     /// Signals that a move has failed to hit a target.
     Miss { m: gamestate::Move },
-    /// Signals to the other peer that the player is ready.
-    Ready,
+    /// Signals to the other peer that the player is ready,
+    /// committing to the nonce of the coin flip that decides on the first turn.
+    Ready { commitment: String },
+    /// Reveals the nonce of the coin flip, once both commitments were exchanged.
+    Flip { nonce: String },
+    /// Signals that the player placed their ships and is ready for the first attack.
+    Placed,
+    /// Proposes a rematch once the game is over.
+    Rematch,
+    /// Accepts the rematch proposed by the other peer.
+    AcceptRematch,
+    /// Reveals the player's ships (as the type and (x, y) coordinates) once the game is over.
+    Reveal { ships: Vec<(ShipType, Vec<(u8, u8)>)> },
+    /// A short text message to the opponent.
+    Chat { text: String },
+    /// Acknowledges the receipt of the message with the given sequence number.
+    Ack { seq: u64 },
 }
 
 impl Message {
+    /// Returns the channel of the p2p network over which the message is sent.
+    pub fn channel(&self) -> Channel {
+        match self {
+            Message::Attack { .. }
+            | Message::Hit { .. }
+            | Message::Miss { .. }
+            | Message::EndGame => Channel::Moves,
+            Message::Ready { .. }
+            | Message::Flip { .. }
+            | Message::Placed
+            | Message::Rematch
+            | Message::AcceptRematch
+            | Message::Reveal { .. }
+            | Message::Ack { .. } => Channel::Control,
+            Message::Chat { .. } => Channel::Chat,
+        }
+    }
+
     /// Validates the contents of the message.
     pub fn validate(&self) -> eyre::Result<()> {
         match self {
@@ -28,23 +101,101 @@ impl Message {
             Message::EndGame => (),
             Message::Hit { m } => m.validate()?,
             Message::Miss { m } => m.validate()?,
-            Message::Ready => (),
+            Message::Ready { .. } => (),
+            Message::Flip { .. } => (),
+            Message::Placed => (),
+            Message::Rematch => (),
+            Message::AcceptRematch => (),
+            Message::Reveal { ships } => {
+                if ships.iter().flat_map(|(_, coords)| coords).any(|(x, y)| {
+                    !(1..=game::GRID_SIZE).contains(x) || !(1..=game::GRID_SIZE).contains(y)
+                }) {
+                    return Err(eyre::eyre!("revealed ship outside of grid"));
+                }
+            }
+            Message::Chat { text } => chat::validate(text)?,
+            Message::Ack { .. } => (),
         }
 
         Ok(())
     }
 }
 
-impl From<Message> for bytes::Bytes {
-    fn from(val: Message) -> Self {
+/// Envelope wraps every message sent between the participants,
+/// identifying the session and the game of the series it belongs to.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Envelope {
+    /// The ID of the session, which is agreed on during the handshake.
+    ///
+    /// NOTE: this is `None` for the ready messages that are sent before the handshake completed.
+    pub session: Option<String>,
+    /// The number of the game in the series, starting at 1.
+    pub game: u32,
+    /// The sequence number of the message, which is acknowledged by the receiver.
+    ///
+    /// NOTE: this is 0 for the acknowledgements and the chat messages, which are not acknowledged.
+    pub seq: u64,
+    pub message: Message,
+}
+
+impl Envelope {
+    /// Returns an upper bound of the size of an encoded envelope, e.g. to validate the maximum
+    /// message size of the p2p network.
+    ///
+    /// NOTE: the largest message is the reveal of the ships, which is bounded by a ship on every point of the grid.
+    /// The chat messages are bounded by their maximum length, where every character takes up to 4 bytes.
+    pub fn max_encoded_size() -> usize {
+        let digest = "f".repeat(64);
+        let points = (1..=GRID_SIZE).flat_map(|x| (1..=GRID_SIZE).map(move |y| (x, y)));
+        let messages = [
+            Message::Ready {
+                commitment: digest.clone(),
+            },
+            Message::Flip { nonce: digest },
+            Message::Attack {
+                m: gamestate::Move::new(u16::MAX, GRID_SIZE, GRID_SIZE, true),
+            },
+            Message::Reveal {
+                ships: points
+                    .map(|point| (ShipType::Battleship(Orientation::Horizontal), vec![point]))
+                    .collect(),
+            },
+            Message::Chat {
+                text: "🚢".repeat(chat::MAX_CHAT_LENGTH),
+            },
+        ];
+
+        messages
+            .into_iter()
+            .map(|message| {
+                bytes::Bytes::from(Envelope {
+                    session: Some("f".repeat(2 * SESSION_ID_LENGTH)),
+                    game: u32::MAX,
+                    seq: u64::MAX,
+                    message,
+                })
+                .len()
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl From<Envelope> for bytes::Bytes {
+    fn from(val: Envelope) -> Self {
         let serialized = serde_yaml::to_string(&val).expect("failed to serialize message");
 
         bytes::Bytes::from(serialized.into_bytes())
     }
 }
 
-impl From<bytes::Bytes> for Message {
-    fn from(value: bytes::Bytes) -> Self {
-        serde_yaml::from_slice(value.iter().as_slice()).expect("failed to deserialize bytes")
+/// Decodes the envelope of a received message.
+///
+/// NOTE: the bytes are received from other peers, so malformed messages must not panic.
+impl TryFrom<bytes::Bytes> for Envelope {
+    type Error = eyre::Report;
+
+    fn try_from(value: bytes::Bytes) -> eyre::Result<Self> {
+        serde_yaml::from_slice(&value).map_err(|e| eyre::eyre!("failed to decode message: {}", e))
     }
 }
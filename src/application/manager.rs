@@ -0,0 +1,792 @@
+//! The game manager multiplexes several simultaneous games over the channels of the p2p network.
+//!
+//! The messages of all games are split into the channels of the session control, the moves and
+//! the chat, so that every kind of message has its own quota and backlog.
+//! Every game is played by its own [`GameStateActor`], which only sends its messages to its opponent.
+//! The incoming messages are routed to the games by their sender and the session ID of the envelope,
+//! so that e.g. stale messages of a previous session don't reach a newer game against the same peer.
+//! The unsolicited messages of peers that aren't an opponent of any running game are rejected.
+use std::fmt;
+
+use bytes::Bytes;
+use commonware_cryptography::{PublicKey, Signer};
+use commonware_macros::select;
+use commonware_p2p::{Receiver, Sender};
+use commonware_runtime::{Clock, ContextCell, Metrics, Spawner, spawn_cell};
+use futures::{
+    StreamExt,
+    channel::mpsc,
+    stream::{self, BoxStream},
+};
+use rand::{CryptoRng, Rng};
+
+use crate::{
+    events::{Event, EventSink},
+    gui::{self, Board, Log, LogLevel, LogType},
+    lobby::Policy,
+    spectator,
+    strategy::Strategy,
+};
+
+use super::{
+    Channel, ChannelSender,
+    actor::GameStateActor,
+    ingress::{Envelope, Message},
+};
+
+/// The number of routed messages that are buffered for every game.
+const GAME_BACKLOG: usize = 16;
+
+/// Builds the strategy of every newly started game.
+pub type StrategyFactory = Box<dyn FnMut() -> Box<dyn Strategy> + Send>;
+
+/// A started game, as tracked by the manager.
+struct Game<P: PublicKey> {
+    /// The number of the game, starting at 1.
+    id: usize,
+    opponent: P,
+    /// The ID of the session, once the opponent revealed it.
+    session: Option<String>,
+    /// The routed messages are sent to the game's actor through this channel.
+    messages: mpsc::Sender<(P, Bytes)>,
+    /// Signals if the game is over, after which it doesn't count as an active game anymore.
+    finished: bool,
+}
+
+/// The game manager starts a game state actor for every opponent and routes the received
+/// messages of the shared p2p channels to them.
+pub struct GameManager<R: Rng + CryptoRng + Spawner + Clock + Metrics, C: Signer> {
+    context: ContextCell<R>,
+    crypto: C,
+
+    // The events of all games are published to the subscribers of the sink (e.g. the NDJSON writer).
+    events: EventSink,
+
+    /// The GUI, which shows a board for every started game (if any).
+    gui: Option<gui::Mailbox>,
+
+    /// The broadcast actor, which sends the updates of every started game to the spectators (if any).
+    spectators: Option<spectator::Mailbox>,
+
+    /// The minimum level of the published logs.
+    log_level: LogLevel,
+
+    /// Builds the strategy of every newly started game.
+    strategies: StrategyFactory,
+
+    /// The policy which peers are accepted as opponents.
+    policy: Policy<C::PublicKey>,
+
+    /// The opponents to start a game against, e.g. as chosen in the lobby.
+    ///
+    /// NOTE: this is taken out of the actor once it's running, so that it can be polled
+    /// while routing the messages.
+    opponents: Option<mpsc::Receiver<C::PublicKey>>,
+
+    /// The opponents of the finished games are sent through this channel (if any), e.g. to the lobby.
+    ///
+    /// NOTE: the channel is unbounded, so that the manager and the lobby can't block each other
+    /// while handing over the opponents. It's bounded by the number of started games anyway.
+    finished: Option<mpsc::UnboundedSender<C::PublicKey>>,
+
+    /// The number of started games, which numbers the games.
+    started: usize,
+
+    /// The started games that were not superseded yet, in the order they were started.
+    games: Vec<Game<C::PublicKey>>,
+}
+
+impl<R: Rng + CryptoRng + Spawner + Clock + Metrics, C: Signer> GameManager<R, C> {
+    /// Creates a new game manager, which starts a game against every received opponent.
+    pub fn new(
+        context: R,
+        events: EventSink,
+        gui: Option<gui::Mailbox>,
+        log_level: LogLevel,
+        crypto: C,
+        strategies: StrategyFactory,
+        opponents: mpsc::Receiver<C::PublicKey>,
+    ) -> Self {
+        Self {
+            context: ContextCell::new(context),
+            crypto,
+            events,
+            gui,
+            spectators: None,
+            log_level,
+            strategies,
+            policy: Policy::default(),
+            opponents: Some(opponents),
+            finished: None,
+            started: 0,
+            games: Vec::new(),
+        }
+    }
+
+    /// Broadcasts the updates of every started game to the spectators through the given mailbox.
+    pub fn with_spectators(mut self, spectators: spectator::Mailbox) -> Self {
+        self.spectators = Some(spectators);
+        self
+    }
+
+    /// Only starts the games against the opponents that are accepted by the given policy.
+    pub fn with_policy(mut self, policy: Policy<C::PublicKey>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sends the opponent of every finished game through the given channel, e.g. to the lobby.
+    pub fn with_finished(mut self, finished: mpsc::UnboundedSender<C::PublicKey>) -> Self {
+        self.finished = Some(finished);
+        self
+    }
+
+    /// Starts the manager with the sender and the receiver of the channels of the session control,
+    /// the moves and the chat.
+    pub fn start<S, R>(mut self, control: (S, R), moves: (S, R), chat: (S, R))
+    where
+        S: Sender<PublicKey = C::PublicKey>,
+        R: Receiver<PublicKey = C::PublicKey>,
+    {
+        spawn_cell!(self.context, self.run(control, moves, chat).await);
+    }
+
+    async fn run<S, R>(mut self, control: (S, R), moves: (S, R), chat: (S, R))
+    where
+        S: Sender<PublicKey = C::PublicKey>,
+        R: Receiver<PublicKey = C::PublicKey>,
+    {
+        let sender = ChannelSender::new(control.0, moves.0, chat.0);
+        let (mut control, mut moves, mut chat) = (control.1, moves.1, chat.1);
+
+        // NOTE: the opponents are chained with a pending stream, so that the loop is not woken up
+        // repeatedly once no more opponents are chosen (e.g. without a lobby).
+        let mut opponents = self
+            .opponents
+            .take()
+            .expect("actor must only be run once")
+            .chain(stream::pending());
+
+        // The events of all games are merged into a single stream, tagged with their ID,
+        // so that the manager learns when a game is over.
+        // NOTE: the pending stream keeps the merged stream from ending while there are no games.
+        let mut events = stream::select_all([stream::pending().boxed()]);
+
+        let mut stopped = self.context.as_present().stopped();
+
+        loop {
+            select! {
+                _ = &mut stopped => {
+                    break;
+                },
+                opponent = opponents.next() => {
+                    if let Some(opponent) = opponent
+                        && let Some(game_events) = self.start_game(sender.clone(), opponent).await
+                    {
+                        events.push(game_events);
+                    }
+                },
+                event = events.next() => {
+                    if let Some((id, Event::GameOver { .. })) = event {
+                        self.game_over(id);
+                    }
+                },
+                msg = control.recv() => {
+                    if !self.received(Channel::Control, msg.ok()).await {
+                        break;
+                    }
+                },
+                msg = moves.recv() => {
+                    if !self.received(Channel::Moves, msg.ok()).await {
+                        break;
+                    }
+                },
+                msg = chat.recv() => {
+                    if !self.received(Channel::Chat, msg.ok()).await {
+                        break;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Routes the message that was received over the given channel.
+    ///
+    /// Returns false if the channel failed, after which the manager is stopped.
+    async fn received(&mut self, channel: Channel, msg: Option<(C::PublicKey, Bytes)>) -> bool {
+        let Some((peer, message_bytes)) = msg else {
+            self.log(LogType::Error, "failed to receive message").await;
+            return false;
+        };
+
+        self.route(channel, peer, message_bytes).await;
+        true
+    }
+
+    /// Starts a new game against the given opponent, adding its board to the GUI.
+    ///
+    /// Returns the events of the game, tagged with its ID, or `None` if the game was refused
+    /// because the opponent is not accepted by the policy.
+    async fn start_game(
+        &mut self,
+        sender: ChannelSender<impl Sender<PublicKey = C::PublicKey>>,
+        opponent: C::PublicKey,
+    ) -> Option<BoxStream<'static, (usize, Event)>> {
+        if !self.policy.allows(&opponent) {
+            self.log(
+                LogType::Error,
+                &format!("refusing game against {}: not allowed", opponent),
+            )
+            .await;
+            return None;
+        }
+        if self.policy.single_opponent()
+            && let Some(game) = self.games.iter().find(|game| !game.finished)
+        {
+            let content = format!(
+                "refusing game against {}: already playing against {}",
+                opponent, game.opponent
+            );
+            self.log(LogType::Error, &content).await;
+            return None;
+        }
+
+        // A finished game is superseded by a new game against the same opponent, or against any
+        // opponent if only a single opponent is played against.
+        // NOTE: this stops the actor of the finished game, which ends a pending rematch.
+        let single_opponent = self.policy.single_opponent();
+        self.games
+            .retain(|game| !game.finished || (!single_opponent && game.opponent != opponent));
+
+        self.started += 1;
+        let id = self.started;
+        let (messages, receiver) = mpsc::channel(GAME_BACKLOG);
+
+        // Every game publishes its events to the shared subscribers, its own board and the manager.
+        let mut events = self.events.clone();
+        let (manager_sender, manager_receiver) = mpsc::channel(1);
+        events.subscribe(manager_sender);
+        let mut board = None;
+        if self.gui.is_some() {
+            let (board_sender, board_receiver) = mpsc::channel(1);
+            events.subscribe(board_sender);
+            board = Some(board_receiver);
+        }
+        let mut broadcast = None;
+        if self.spectators.is_some() {
+            let (broadcast_sender, broadcast_receiver) = mpsc::channel(1);
+            events.subscribe(broadcast_sender);
+            broadcast = Some(broadcast_receiver);
+        }
+
+        let (actor, mailbox) = GameStateActor::new(
+            self.context
+                .as_present()
+                .with_label(&format!("game_{}", id)),
+            events,
+            self.log_level,
+            self.crypto.clone(),
+            opponent.clone(),
+            (self.strategies)(),
+        );
+
+        // NOTE: the GUI and the broadcast actor are only dropped if the node is shutting down.
+        if let (Some(spectators), Some(events)) = (self.spectators.as_mut(), broadcast) {
+            let _ = spectators
+                .add_game(spectator::Game {
+                    opponent: opponent.to_string(),
+                    events,
+                })
+                .await;
+        }
+        if let (Some(gui), Some(events)) = (self.gui.as_mut(), board) {
+            let title = format!("Game {}: {}", id, short_key(&opponent));
+            let _ = gui
+                .add_board(Board {
+                    title,
+                    events,
+                    mailbox: Some(mailbox),
+                })
+                .await;
+        }
+
+        actor.start(sender, GameReceiver { messages: receiver });
+        self.log(
+            LogType::Info,
+            &format!("🎮 started game {} against {}", id, opponent),
+        )
+        .await;
+
+        self.games.push(Game {
+            id,
+            opponent,
+            session: None,
+            messages,
+            finished: false,
+        });
+
+        Some(manager_receiver.map(move |event| (id, event)).boxed())
+    }
+
+    /// Marks the game with the given ID as finished and hands its opponent over,
+    /// e.g. to be challenged again in the lobby.
+    ///
+    /// NOTE: the game may still be continued with a rematch until it's superseded by a new game,
+    /// but it doesn't count as an active game anymore.
+    fn game_over(&mut self, id: usize) {
+        let Some(game) = self
+            .games
+            .iter_mut()
+            .find(|game| game.id == id && !game.finished)
+        else {
+            return;
+        };
+        game.finished = true;
+
+        if let Some(finished) = &self.finished {
+            // NOTE: the receiver is only dropped if the node is shutting down.
+            let _ = finished.unbounded_send(game.opponent.clone());
+        }
+    }
+
+    /// Routes the received message to the game it belongs to.
+    ///
+    /// The unsolicited messages of peers that aren't an opponent of any running game are rejected.
+    async fn route(&mut self, channel: Channel, peer: C::PublicKey, message_bytes: Bytes) {
+        // NOTE: the opponent is checked before decoding the message, so that other peers
+        // can't make the node fail with malformed messages.
+        if !self.games.iter().any(|game| game.opponent == peer) {
+            return self
+                .log(
+                    LogType::Debug,
+                    &format!("rejecting unsolicited message of {}", peer),
+                )
+                .await;
+        }
+
+        // The messages must be sent over their own channel, so that they're subject to its quota.
+        let envelope = match Envelope::try_from(message_bytes.clone()) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                return self
+                    .log(
+                        LogType::Debug,
+                        &format!("dropping malformed message of {}: {}", peer, e),
+                    )
+                    .await;
+            }
+        };
+        if envelope.message.channel() != channel {
+            return self
+                .log(
+                    LogType::Debug,
+                    &format!("rejecting message of {} on the {:?} channel", peer, channel),
+                )
+                .await;
+        }
+
+        let Some(index) = self.find_game(&peer, envelope.session.as_deref()) else {
+            return self
+                .log(
+                    LogType::Debug,
+                    &format!("dropping message of {} of an unknown session", peer),
+                )
+                .await;
+        };
+
+        // NOTE: the nonce of the coin flip is the first message the opponent sends in a new session,
+        // so it's used to learn the game's session. Any other message of an unknown session is
+        // routed to the game as well, which then drops it.
+        let game = &mut self.games[index];
+        if game.session.is_none() && matches!(envelope.message, Message::Flip { .. }) {
+            game.session = envelope.session;
+        }
+
+        // NOTE: the message is dropped if the game's backlog is full, so that a slow game can't
+        // stall the routing of the other games. Lost moves are retransmitted by the opponent anyway.
+        if let Err(e) = game.messages.try_send((peer, message_bytes)) {
+            let content = if e.is_full() {
+                format!("dropping message of game {}: backlog is full", game.id)
+            } else {
+                format!("dropping message of stopped game {}", game.id)
+            };
+            self.log(LogType::Debug, &content).await;
+        }
+    }
+
+    /// Returns the index of the game against the given peer, which the message of the given session
+    /// belongs to.
+    ///
+    /// Messages of an unknown session are routed to the game against the peer that did not learn
+    /// its session yet, if any.
+    fn find_game(&self, peer: &C::PublicKey, session: Option<&str>) -> Option<usize> {
+        let games = || {
+            self.games
+                .iter()
+                .enumerate()
+                .filter(|(_, game)| game.opponent == *peer)
+        };
+
+        let found = match session {
+            Some(session) => games().find(|(_, game)| game.session.as_deref() == Some(session)),
+            // The ready messages are retransmitted, so they might still arrive once the session is known.
+            None => games()
+                .find(|(_, game)| game.session.is_none())
+                .or_else(|| games().next()),
+        };
+
+        found
+            .or_else(|| games().find(|(_, game)| game.session.is_none()))
+            .map(|(index, _)| index)
+    }
+
+    /// Publishes the given log, if it matches the configured log level.
+    async fn log(&mut self, log_type: LogType, content: &str) {
+        if log_type.level() < self.log_level {
+            return;
+        }
+
+        self.events
+            .publish(Event::Log {
+                log: Log::new(log_type, content.into()),
+            })
+            .await;
+    }
+}
+
+/// Receives the messages that were routed to a single game by the manager.
+#[derive(Debug)]
+struct GameReceiver<P: PublicKey> {
+    messages: mpsc::Receiver<(P, Bytes)>,
+}
+
+impl<P: PublicKey> Receiver for GameReceiver<P> {
+    type Error = Stopped;
+    type PublicKey = P;
+
+    async fn recv(&mut self) -> Result<(P, Bytes), Self::Error> {
+        self.messages.next().await.ok_or(Stopped)
+    }
+}
+
+/// The error of a game's receiver once the manager stopped.
+#[derive(Debug)]
+struct Stopped;
+
+impl fmt::Display for Stopped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "game manager stopped")
+    }
+}
+
+impl std::error::Error for Stopped {}
+
+/// Shortens the given public key to fit into the title of a board.
+fn short_key(public_key: &impl PublicKey) -> String {
+    public_key.to_string().chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use commonware_cryptography::{
+        PrivateKeyExt as _,
+        ed25519::{self, PrivateKey},
+    };
+    use commonware_p2p::simulated::{self, Link, Network};
+    use commonware_runtime::{Runner, deterministic};
+    use futures::SinkExt;
+
+    use crate::{
+        config::{CHAT_CHANNEL, CONTROL_CHANNEL, MOVES_CHANNEL},
+        strategy::HuntTarget,
+    };
+
+    #[test]
+    fn test_multiple_games() {
+        // the first player plays against both other players at the same time
+        let games = deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+
+            let signers = (0..3).map(PrivateKey::from_seed).collect::<Vec<_>>();
+            for (from, to) in [(0, 1), (1, 0), (0, 2), (2, 0)] {
+                oracle
+                    .add_link(
+                        signers[from].public_key(),
+                        signers[to].public_key(),
+                        Link {
+                            latency: Duration::from_millis(10),
+                            jitter: Duration::ZERO,
+                            success_rate: 1.0,
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            let mut receivers = Vec::new();
+            for (id, signer) in signers.iter().enumerate() {
+                let mut control = oracle.control(signer.public_key());
+                let mut channels = Vec::new();
+                for channel in [CONTROL_CHANNEL, MOVES_CHANNEL, CHAT_CHANNEL] {
+                    channels.push(control.register(channel).await.unwrap());
+                }
+
+                let (mut opponents_sender, opponents) = mpsc::channel(2);
+                let opponents_ids = match id {
+                    0 => vec![1, 2],
+                    _ => vec![0],
+                };
+                for opponent in opponents_ids {
+                    opponents_sender
+                        .send(signers[opponent].public_key())
+                        .await
+                        .unwrap();
+                }
+
+                let (events_sender, events_receiver) = mpsc::channel(1024);
+                let manager = GameManager::new(
+                    context.with_label(&format!("manager_{}", id)),
+                    EventSink::new(vec![events_sender]),
+                    None,
+                    LogLevel::Info,
+                    signer.clone(),
+                    Box::new(|| Box::new(HuntTarget::new()) as Box<dyn Strategy>),
+                    opponents,
+                );
+                manager.start(channels.remove(0), channels.remove(0), channels.remove(0));
+
+                receivers.push(events_receiver.map(move |event| (id, event)));
+            }
+
+            // Every game is over once both players published its summary.
+            let mut events = stream::select_all(receivers);
+            let mut games = [0; 3];
+            while games.iter().sum::<usize>() < 4 {
+                let (id, event) = events.next().await.expect("manager stopped");
+                match event {
+                    Event::GameOver { .. } => games[id] += 1,
+                    Event::Error { message } => panic!("player {} failed: {}", id, message),
+                    _ => (),
+                }
+            }
+
+            games
+        });
+
+        assert_eq!([2, 1, 1], games);
+    }
+
+    /// Creates a manager of the first player, which isn't started yet.
+    fn new_manager(
+        context: deterministic::Context,
+        policy: Policy<ed25519::PublicKey>,
+    ) -> GameManager<deterministic::Context, PrivateKey> {
+        let (_, opponents) = mpsc::channel(1);
+        GameManager::new(
+            context,
+            EventSink::default(),
+            None,
+            LogLevel::Info,
+            PrivateKey::from_seed(0),
+            Box::new(|| Box::new(HuntTarget::new()) as Box<dyn Strategy>),
+            opponents,
+        )
+        .with_policy(policy)
+    }
+
+    #[test]
+    fn test_policy() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+            let (sender, _) = oracle
+                .control(PrivateKey::from_seed(0).public_key())
+                .register(0)
+                .await
+                .unwrap();
+
+            let [first, second, third] =
+                [1, 2, 3].map(|seed| PrivateKey::from_seed(seed).public_key());
+            let sender = ChannelSender::single(sender);
+            let policy = Policy::new(None, vec![third.clone()], true);
+            let (finished, mut finished_receiver) = mpsc::unbounded();
+            let mut manager =
+                new_manager(context.with_label("manager"), policy).with_finished(finished);
+
+            // the blocked peer is refused, as well as any opponent after the first one
+            // NOTE: the events of the started games are kept, so that their actors can publish them.
+            let blocked = manager.start_game(sender.clone(), third).await;
+            assert!(blocked.is_none() && manager.games.is_empty());
+            let first_events = manager.start_game(sender.clone(), first.clone()).await;
+            let second_events = manager.start_game(sender.clone(), second.clone()).await;
+            assert!(first_events.is_some() && second_events.is_none());
+            assert_eq!(1, manager.games.len());
+            assert_eq!(first, manager.games[0].opponent);
+
+            // once the game is over, its opponent is handed over and the next opponent is accepted,
+            // which supersedes the finished game
+            manager.game_over(1);
+            assert_eq!(Some(first), finished_receiver.try_next().unwrap());
+            let second_events = manager.start_game(sender, second.clone()).await;
+            assert!(second_events.is_some());
+            assert_eq!(1, manager.games.len());
+            assert_eq!(2, manager.games[0].id);
+            assert_eq!(second, manager.games[0].opponent);
+        });
+    }
+
+    #[test]
+    fn test_stopped_manager() {
+        // the game actor stops once the manager stopped routing its messages
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+            let (sender, _) = oracle
+                .control(PrivateKey::from_seed(0).public_key())
+                .register(0)
+                .await
+                .unwrap();
+
+            let (actor, _) = GameStateActor::new(
+                context.with_label("game"),
+                EventSink::default(),
+                LogLevel::Info,
+                PrivateKey::from_seed(0),
+                PrivateKey::from_seed(1).public_key(),
+                Box::new(HuntTarget::new()),
+            );
+            let (_, messages) = mpsc::channel(GAME_BACKLOG);
+            actor
+                .run(ChannelSender::single(sender), GameReceiver { messages })
+                .await;
+        });
+    }
+
+    #[test]
+    fn test_unsolicited_messages() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let mut manager = new_manager(context.with_label("manager"), Policy::default());
+            let [opponent, intruder] = [1, 2].map(|seed| PrivateKey::from_seed(seed).public_key());
+
+            let (messages, mut receiver) = mpsc::channel(GAME_BACKLOG);
+            manager.games.push(Game {
+                id: 1,
+                opponent: opponent.clone(),
+                session: None,
+                messages,
+                finished: false,
+            });
+
+            // the malformed messages of other peers are rejected without decoding them
+            manager
+                .route(
+                    Channel::Control,
+                    intruder.clone(),
+                    Bytes::from_static(b"\x00invalid"),
+                )
+                .await;
+            let flip: Bytes = Envelope {
+                session: Some("session".into()),
+                game: 1,
+                seq: 1,
+                message: Message::Flip {
+                    nonce: String::new(),
+                },
+            }
+            .into();
+            manager
+                .route(Channel::Control, intruder, flip.clone())
+                .await;
+            assert!(receiver.try_next().is_err());
+
+            // the malformed messages of the opponent are dropped
+            manager
+                .route(
+                    Channel::Control,
+                    opponent.clone(),
+                    Bytes::from_static(b"\x00invalid"),
+                )
+                .await;
+            assert!(receiver.try_next().is_err());
+
+            // the messages of the opponent must be sent over their own channel
+            manager
+                .route(Channel::Moves, opponent.clone(), flip.clone())
+                .await;
+            assert!(receiver.try_next().is_err());
+
+            // the messages of the opponent are routed to its game
+            manager
+                .route(Channel::Control, opponent.clone(), flip.clone())
+                .await;
+            assert_eq!(Some((opponent, flip)), receiver.try_next().unwrap());
+            assert_eq!(Some("session".into()), manager.games[0].session);
+        });
+    }
+
+    #[test]
+    fn test_full_backlog() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let mut manager = new_manager(context.with_label("manager"), Policy::default());
+            let opponent = PrivateKey::from_seed(1).public_key();
+
+            let (messages, mut receiver) = mpsc::channel(GAME_BACKLOG);
+            manager.games.push(Game {
+                id: 1,
+                opponent: opponent.clone(),
+                session: None,
+                messages,
+                finished: false,
+            });
+
+            // the messages beyond the backlog are dropped instead of blocking the routing
+            let flip: Bytes = Envelope {
+                session: Some("session".into()),
+                game: 1,
+                seq: 1,
+                message: Message::Flip {
+                    nonce: String::new(),
+                },
+            }
+            .into();
+            for _ in 0..2 * GAME_BACKLOG {
+                manager
+                    .route(Channel::Control, opponent.clone(), flip.clone())
+                    .await;
+            }
+
+            let mut routed = 0;
+            while let Ok(Some(_)) = receiver.try_next() {
+                routed += 1;
+            }
+            // NOTE: the channel buffers an additional message for its single sender.
+            assert_eq!(GAME_BACKLOG + 1, routed);
+        });
+    }
+}
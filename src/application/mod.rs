@@ -1,3 +1,17 @@
 pub mod actor;
+mod channel;
+mod chat;
+mod coinflip;
+mod delivery;
 mod gamestate;
 mod ingress;
+pub mod manager;
+mod session;
+mod summary;
+
+pub use channel::{Channel, ChannelSender};
+pub use chat::{CHAT_LIMIT, MAX_CHAT_LENGTH};
+pub use coinflip::{CoinFlip, Flip};
+pub use gamestate::Move;
+pub use ingress::{Envelope, Mailbox};
+pub use summary::{Score, Sinking, Stats, Summary, Transcript};
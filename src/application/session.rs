@@ -0,0 +1,192 @@
+//! The state machine of a session between two players.
+//!
+//! A session goes through the following states:
+//!
+//! - `Connecting`: the ready message was sent and the opponent's ready message is awaited.
+//! - `Handshaking`: the commitments of the coin flip were exchanged and the opponent's nonce is awaited.
+//! - `Placing`: the ships were placed and the opponent's ships are awaited.
+//! - `Playing`: the players take turns attacking each other.
+//! - `Finished`: the game is over; the ships are revealed and a rematch may return to `Placing`.
+//!
+//! Every message has a defined handling in every state, so that e.g. duplicate ready messages
+//! of a simultaneous start don't break the session.
+//!
+//! NOTE: the messages are retransmitted until they're acknowledged, but duplicates are already
+//! dropped by the delivery, so they're only handled once.
+
+use std::fmt;
+
+use commonware_cryptography::{Hasher, Sha256};
+use commonware_utils::hex;
+
+use super::ingress::Message;
+
+/// The number of bytes of the session ID.
+pub(super) const SESSION_ID_LENGTH: usize = 16;
+
+/// The state of the session with the opponent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    Connecting,
+    Handshaking,
+    Placing,
+    Playing,
+    Finished,
+}
+
+/// How an incoming message is handled in a given state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Handling {
+    /// The message is processed.
+    Handle,
+    /// The message is expected to arrive late or repeatedly (e.g. duplicate ready messages), so it's dropped.
+    Ignore,
+    /// The message violates the protocol.
+    Reject,
+}
+
+impl State {
+    /// Returns how the given message is handled in this state.
+    pub fn handling(&self, message: &Message) -> Handling {
+        use Handling::*;
+        use State::*;
+
+        match (message, self) {
+            // The ready message is retransmitted until it's acknowledged,
+            // so it can still arrive after the handshake was completed.
+            (Message::Ready { .. }, Connecting | Handshaking) => Handle,
+            (Message::Ready { .. }, Placing | Playing | Finished) => Ignore,
+
+            // The nonce is only revealed once, after the commitments were exchanged.
+            (Message::Flip { .. }, Handshaking) => Handle,
+            (Message::Flip { .. }, _) => Reject,
+
+            (Message::Placed, Placing) => Handle,
+            (Message::Placed, _) => Reject,
+
+            // Moves that were in flight while the game ended (e.g. due to an error) are dropped.
+            (Message::Attack { .. } | Message::Hit { .. } | Message::Miss { .. }, Playing) => {
+                Handle
+            }
+            (Message::Attack { .. } | Message::Hit { .. } | Message::Miss { .. }, Finished) => {
+                Ignore
+            }
+            (Message::Attack { .. } | Message::Hit { .. } | Message::Miss { .. }, _) => Reject,
+
+            (Message::EndGame, Playing) => Handle,
+            (Message::EndGame, Finished) => Ignore,
+            (Message::EndGame, _) => Reject,
+
+            (Message::Reveal { .. } | Message::Rematch | Message::AcceptRematch, Finished) => {
+                Handle
+            }
+            (Message::Reveal { .. } | Message::Rematch | Message::AcceptRematch, _) => Reject,
+
+            // The chat is sent over its own channel, so it may overtake the ready message of the opponent.
+            // Once the session was agreed on, it's accepted in every state, e.g. also between the games.
+            (Message::Chat { .. }, Connecting) => Ignore,
+            (Message::Chat { .. }, _) => Handle,
+
+            // The acknowledgements are handled by the delivery, before the session is checked.
+            (Message::Ack { .. }, _) => Ignore,
+        }
+    }
+}
+
+/// Derives the ID of the session from the coin flip commitments of both players.
+///
+/// The commitments are ordered, so that both players derive the same ID. Since the commitments
+/// are hashes of random nonces, the ID is random as well, without requiring an additional message.
+pub fn session_id(own_commitment: &str, opponent_commitment: &str) -> String {
+    let (lower, higher) = match own_commitment < opponent_commitment {
+        true => (own_commitment, opponent_commitment),
+        false => (opponent_commitment, own_commitment),
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(lower.as_bytes());
+    hasher.update(higher.as_bytes());
+
+    hex(&hasher.finalize().as_ref()[..SESSION_ID_LENGTH])
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            State::Connecting => "connecting",
+            State::Handshaking => "handshaking",
+            State::Placing => "placing",
+            State::Playing => "playing",
+            State::Finished => "finished",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::Move;
+
+    #[test]
+    fn test_handling() {
+        let ready = Message::Ready {
+            commitment: "commitment".into(),
+        };
+        let attack = Message::Attack {
+            m: Move::new(1, 1, 1, false),
+        };
+
+        // simultaneous ready messages are handled during the handshake and dropped afterwards
+        assert_eq!(Handling::Handle, State::Connecting.handling(&ready));
+        assert_eq!(Handling::Handle, State::Handshaking.handling(&ready));
+        assert_eq!(Handling::Ignore, State::Playing.handling(&ready));
+        assert_eq!(Handling::Ignore, State::Finished.handling(&ready));
+
+        let flip = Message::Flip {
+            nonce: "nonce".into(),
+        };
+        assert_eq!(Handling::Reject, State::Connecting.handling(&flip));
+        assert_eq!(Handling::Handle, State::Handshaking.handling(&flip));
+        assert_eq!(Handling::Reject, State::Playing.handling(&flip));
+
+        assert_eq!(
+            Handling::Reject,
+            State::Handshaking.handling(&Message::Placed)
+        );
+        assert_eq!(Handling::Handle, State::Placing.handling(&Message::Placed));
+
+        // attacks are only played once both players placed their ships
+        assert_eq!(Handling::Reject, State::Connecting.handling(&attack));
+        assert_eq!(Handling::Reject, State::Placing.handling(&attack));
+        assert_eq!(Handling::Handle, State::Playing.handling(&attack));
+        assert_eq!(Handling::Ignore, State::Finished.handling(&attack));
+
+        assert_eq!(Handling::Reject, State::Playing.handling(&Message::Rematch));
+        assert_eq!(
+            Handling::Handle,
+            State::Finished.handling(&Message::Rematch)
+        );
+        assert_eq!(
+            Handling::Reject,
+            State::Playing.handling(&Message::Reveal { ships: vec![] })
+        );
+
+        let chat = Message::Chat { text: "gl".into() };
+        assert_eq!(Handling::Ignore, State::Connecting.handling(&chat));
+        assert_eq!(Handling::Handle, State::Playing.handling(&chat));
+        assert_eq!(Handling::Handle, State::Finished.handling(&chat));
+    }
+
+    #[test]
+    fn test_session_id() {
+        let id = session_id("ab", "cd");
+        assert_eq!(SESSION_ID_LENGTH * 2, id.len());
+
+        // both players derive the same ID
+        assert_eq!(id, session_id("cd", "ab"));
+        assert_ne!(id, session_id("ab", "ce"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Statistics and transcripts of finished games.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{
+    events::{Event, Side},
+    game::{Cell, Ship},
+};
+
+use super::{Move, coinflip::Flip};
+
+/// The number of shots a player needed to sink a ship.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Sinking {
+    pub ship: String,
+    /// The number of shots fired until the ship was sunk, or `None` if it was not sunk.
+    pub shots: Option<usize>,
+}
+
+/// The statistics of the attacks of a single player.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Stats {
+    pub shots: usize,
+    pub hits: usize,
+    pub hit_rate: f64,
+    /// The longest number of consecutive hits.
+    pub longest_streak: usize,
+    /// The shots needed to sink each of the attacked ships.
+    pub sinkings: Vec<Sinking>,
+}
+
+impl Stats {
+    /// Computes the statistics of the given attacks against the given ships.
+    pub fn new(moves: &[Move], ships: &[Ship]) -> Self {
+        let hits = moves.iter().filter(|m| m.is_hit).count();
+        let hit_rate = match moves.len() {
+            0 => 0.0,
+            shots => hits as f64 / shots as f64,
+        };
+
+        let longest_streak = moves
+            .iter()
+            .scan(0, |streak, m| {
+                *streak = if m.is_hit { *streak + 1 } else { 0 };
+                Some(*streak)
+            })
+            .max()
+            .unwrap_or_default();
+
+        // A ship is sunk with the last shot that hit one of its coordinates.
+        let sinkings = ships
+            .iter()
+            .map(|ship| Sinking {
+                ship: ship.type_.name().to_string(),
+                shots: ship
+                    .coords
+                    .iter()
+                    .map(|c| {
+                        moves
+                            .iter()
+                            .position(|m| m.get_x() == c.x && m.get_y() == c.y)
+                    })
+                    .collect::<Option<Vec<usize>>>()
+                    .and_then(|positions| positions.into_iter().max())
+                    .map(|position| position + 1),
+            })
+            .collect();
+
+        Self {
+            shots: moves.len(),
+            hits,
+            hit_rate,
+            longest_streak,
+            sinkings,
+        }
+    }
+}
+
+/// The running score of a series of games against the same opponent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Score {
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl Score {
+    /// Records the result of a game with the given winner.
+    pub fn record(&mut self, winner: Side) {
+        match winner {
+            Side::Own => self.wins += 1,
+            Side::Opponent => self.losses += 1,
+        }
+    }
+}
+
+/// The summary of a finished game, which is shown on the game-over screen.
+#[derive(Clone, Debug, Serialize)]
+pub struct Summary {
+    pub winner: Side,
+    pub duration: Duration,
+    /// The statistics of the player's own attacks.
+    pub own: Stats,
+    /// The statistics of the opponent's attacks.
+    pub opponent: Stats,
+    /// The score of the series, including this game.
+    pub series: Score,
+    /// The player's own board, including all ships.
+    #[serde(skip)]
+    pub own_board: Vec<Vec<Cell>>,
+    /// The opponent's board, including the ships revealed at the end of the game.
+    #[serde(skip)]
+    pub opponent_board: Vec<Vec<Cell>>,
+    /// Signals if the opponent's reveal was rejected, e.g. because it contradicts the results
+    /// of the attacks. The opponent's board then only shows the attacks.
+    pub disputed: bool,
+}
+
+/// The record of a game, which can be saved once the game is over.
+#[derive(Debug, Serialize)]
+pub struct Transcript {
+    /// The ID of the session, in which the game was played.
+    pub session: String,
+    /// The number of the game in the series of the session.
+    pub game: u32,
+    /// The unix timestamp (in seconds) at which the game started.
+    pub started: u64,
+    /// The player that had the first turn.
+    pub first: Option<Side>,
+    /// The coin flip that decided on the first turn.
+    ///
+    /// NOTE: this is not set for rematches, where the first turn alternates.
+    pub coin_flip: Option<Flip>,
+    /// The results of all attacks of both players, in the order they were played.
+    pub moves: Vec<Event>,
+    /// The summary of the game, if the opponent revealed their ships.
+    pub summary: Option<Summary>,
+}
+
+impl Transcript {
+    /// Exports the transcript as JSON to the given filepath.
+    pub fn export(&self, filepath: &str) -> eyre::Result<()> {
+        let path = std::path::Path::new(filepath);
+        if let Some(dir_path) = path.parent()
+            && !dir_path.exists()
+        {
+            std::fs::create_dir_all(dir_path)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::{Coordinate, Orientation, ShipType};
+
+    #[test]
+    fn test_stats() {
+        let ships = vec![
+            Ship::new(ShipType::Boat, vec![Coordinate::from((1, 1))]),
+            Ship::new(
+                ShipType::Destroyer(Orientation::Horizontal),
+                vec![Coordinate::from((3, 3)), Coordinate::from((4, 3))],
+            ),
+        ];
+        let moves = vec![
+            Move::new(1, 1, 1, true),
+            Move::new(3, 3, 3, true),
+            Move::new(5, 2, 2, false),
+            Move::new(7, 5, 5, false),
+        ];
+
+        let stats = Stats::new(&moves, &ships);
+        assert_eq!(4, stats.shots);
+        assert_eq!(2, stats.hits);
+        assert_eq!(0.5, stats.hit_rate);
+        assert_eq!(2, stats.longest_streak);
+        assert_eq!(
+            vec![
+                Sinking {
+                    ship: "boat".into(),
+                    shots: Some(1)
+                },
+                Sinking {
+                    ship: "destroyer".into(),
+                    shots: None
+                },
+            ],
+            stats.sinkings
+        );
+
+        assert_eq!(0.0, Stats::new(&[], &ships).hit_rate);
+    }
+}
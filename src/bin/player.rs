@@ -1,26 +1,49 @@
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-    str::FromStr,
+    fs::File,
+    io::{self, Write},
     time::Duration,
 };
 
 use battleship_commonware::{
-    Config, application::actor::GameStateActor, config::parse_public_key, get_config_path,
-    gui::GuiActor,
+    Config,
+    application::manager::{GameManager, StrategyFactory},
+    config::{
+        CHAT_CHANNEL, CONTROL_CHANNEL, LOBBY_CHANNEL, MOVES_CHANNEL, SPECTATOR_CHANNEL,
+        parse_public_key,
+    },
+    events::{EventSink, NdjsonActor},
+    get_config_path,
+    gui::{Board, GuiActor, LogLevel, Theme},
+    lobby::LobbyActor,
+    spectator::BroadcastActor,
+    strategy::{LlmConfig, LlmMetrics, LlmStrategy, MockModel, Strategy, find_model},
 };
 
 use clap::arg;
+use commonware_macros::select;
 use commonware_p2p::{Manager, authenticated::discovery};
-use commonware_runtime::{Metrics, Runner, tokio};
-use commonware_utils::NZU32;
-use governor::Quota;
+use commonware_runtime::{Metrics, Runner, Spawner, tokio};
+use futures::{SinkExt, channel::mpsc};
 use parrot::llm::Model;
 
-const MAX_MESSAGE_SIZE: u16 = 1024;
-
 fn main() {
-    let command = clap::Command::new("battleship-commonware-player")
-        .args([arg!(--"public-key" <PUBKEY> "the player's public key")]);
+    let command = clap::Command::new("battleship-commonware-player").args([
+        arg!(--"public-key" <PUBKEY> "the player's public key"),
+        arg!(--model [MODEL] "the LLM model to use; 'mock' selects an offline mock model")
+            .default_value("cursor"),
+        arg!(--"mock-responses" [FILE] "file with canned responses (one per line) for the mock model"),
+        arg!(--"mock-malformed-rate" [RATE] "rate of malformed responses of the random mock model")
+            .default_value("0.0"),
+        arg!(--theme [THEME] "the color theme of the TUI ('default' or 'colorblind')")
+            .default_value("default"),
+        arg!(--"log-level" [LEVEL] "the minimum level of the shown logs ('debug', 'info' or 'error')")
+            .default_value("info"),
+        arg!(--headless "run without the TUI, writing the game events as newline-delimited JSON"),
+        arg!(--events [FILE] "file to write the game events as newline-delimited JSON to (defaults to stdout in headless mode)"),
+        arg!(--lobby "choose the opponent among the known peers in the lobby, instead of playing the first peer"),
+        arg!(--challenge [PUBKEY] "the known peer to challenge in the lobby once it's online"),
+        arg!(--"accept-challenges" "accept all challenges in the lobby automatically"),
+    ]);
 
     let args = command.get_matches();
     let public_key = parse_public_key(
@@ -29,39 +52,60 @@ fn main() {
     )
     .expect("id must be valid u16");
 
-    let mut available_models: Vec<Box<dyn Model>> = parrot::llm::get_available_models()
-        .expect("failed to get available ai models")
-        .into_iter()
-        // TODO: remove filtering, replace with user selection
-        .filter(|m| m.get_name().to_lowercase().contains("cursor"))
-        .collect();
-    assert!(!available_models.is_empty(), "no available llms found");
-
-    // NOTE: here we're using .remove(0) to create an owned copy of the Box. When indexing the vector using [0], it's returning a borrowed instance.
-    let model = available_models.remove(0);
+    // NOTE: the first model is selected right away, so that an invalid model fails on startup.
+    let mut model = Some(select_model(&args));
+    let theme = Theme::from_name(args.get_one::<String>("theme").expect("must provide --theme"))
+        .expect("invalid theme");
+    let log_level = args
+        .get_one::<String>("log-level")
+        .expect("must provide --log-level")
+        .parse::<LogLevel>()
+        .expect("invalid log level");
+    let headless = args.get_flag("headless");
+    let events_path = args.get_one::<String>("events").cloned();
+    let lobby = args.get_flag("lobby");
+    let challenge = args
+        .get_one::<String>("challenge")
+        .map(|public_key| parse_public_key(public_key).expect("invalid public key to challenge"));
+    let accept_challenges = args.get_flag("accept-challenges");
 
     // We're creating the private keys here that will communicate over the p2p
     // connection, in order to exchange messages about the intended moves in the game.
 
     let config = Config::read(&get_config_path(&public_key)).expect("failed to read config");
+    config.validate().expect("invalid config");
 
-    let peer_public_key = parse_public_key(&config.peer_public_key).expect("invalid public key");
     let signer = config.get_private_key();
 
-    let bootstrappers = vec![(
-        peer_public_key.clone(),
-        SocketAddr::from_str(&config.peer_endpoint).expect("invalid peer endpoint"),
-    )];
-
-    // The p2p setup uses the local config for this proof-of-concept.
-    let p2p_config = discovery::Config::local(
-        signer.clone(),
-        b"BATTLESHIP_NAMESPACE",
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), config.port),
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), config.port),
-        bootstrappers,
-        MAX_MESSAGE_SIZE.into(),
+    // The known peers that are allowed by the policy can be challenged, while the connections
+    // of all other peers are rejected.
+    let policy = config.get_policy().expect("invalid policy");
+    let peers = config
+        .get_peers()
+        .expect("invalid peers")
+        .into_iter()
+        .map(|(public_key, _)| public_key)
+        .filter(|public_key| policy.allows(public_key))
+        .collect::<Vec<_>>();
+    assert!(
+        challenge.as_ref().is_none_or(|peer| peers.contains(peer)),
+        "must challenge a known peer that's allowed by the policy"
     );
+    let spectators = config.get_spectators().expect("invalid spectators");
+    let bootstrappers = config
+        .get_bootstrappers()
+        .expect("invalid bootstrappers")
+        .into_iter()
+        .map(|(public_key, _)| public_key)
+        .collect::<Vec<_>>();
+
+    // The allowed peers and the additional bootstrappers are used to bootstrap the connection
+    // to the p2p network, with the addresses and preset of the network config.
+    let p2p_config = config.get_p2p_config().expect("invalid network config");
+
+    // The quotas and backlogs of the channels are taken from the preset of the game mode,
+    // unless they're configured explicitly.
+    let channels = config.get_channels().expect("invalid channels");
 
     // // TODO: ideally abstract at some point to use the deterministic runner in tests / simulations (should be good for debugging).
     // let runner_config = deterministic::Config::new()
@@ -78,41 +122,214 @@ fn main() {
 
         // We set the peers in the oracle (which in the context of commonware-p2p is
         // the central entity to manage the list of connected peers).
+        //
+        // NOTE: the spectators and the additional bootstrappers are added as well, so that they
+        // can connect to watch the games and to discover the peers.
         let peers_index = 0;
-        oracle
-            .update(peers_index, vec![peer_public_key].into())
-            .await;
+        let authorized = peers
+            .iter()
+            .chain(&spectators)
+            .chain(&bootstrappers)
+            .cloned()
+            .collect::<Vec<_>>();
+        oracle.update(peers_index, authorized.into()).await;
 
-        // This registes the channel over which communication
-        // about the game state will be implemented.
+        // This registers the channels over which communication about the game state will be
+        // implemented, where the session control messages, the moves and the chat each use their
+        // own channel with a separate quota and backlog.
         //
         // Importantly, this only relates to the communication that happens over the p2p layer.
         // The GUI actor that's set up below does not receive any information from another peer,
         // hence it's not required to register the channel for the network.
         // Instead, all updates to the GUI actor are driven internally in the same binary,
         // controlled by the game state actor.
-        let (gamestate_sender, gamestate_receiver) =
-            network.register(0, Quota::per_second(NZU32!(1)), 1);
+        let control = network.register(
+            CONTROL_CHANNEL,
+            channels.control.quota(),
+            channels.control.backlog,
+        );
+        let moves = network.register(
+            MOVES_CHANNEL,
+            channels.moves.quota(),
+            channels.moves.backlog,
+        );
+        let chat = network.register(CHAT_CHANNEL, channels.chat.quota(), channels.chat.backlog);
+
+        // The lobby uses a separate channel, so that the announcements of the known peers
+        // don't interfere with the game.
+        let (lobby_sender, lobby_receiver) = network.register(
+            LOBBY_CHANNEL,
+            channels.lobby.quota(),
+            channels.lobby.backlog,
+        );
+
+        // The spectators subscribe to the updates of the games on another channel.
+        let (spectator_sender, spectator_receiver) = network.register(
+            SPECTATOR_CHANNEL,
+            channels.spectators.quota(),
+            channels.spectators.backlog,
+        );
 
-        // Here we're setting up the actor that updates the game state as well as
-        // the actor that is doing the TUI updates.
+        // Here we're setting up the game manager that starts a game state actor for every opponent,
+        // as well as the actor that is doing the TUI updates.
         //
-        // After the initial setup we have to start the actor, providing
+        // After the initial setup we have to start the manager, providing
         // the registered channels for p2p communication.
         //
-        // The game state actor then handles the exchange of game actions, etc. while
-        // driving the GUI actor to output the current state of the game.
-        let (gui_actor, gui_mailbox) = GuiActor::new(context.with_label("gui"));
-        let gamestate_actor = GameStateActor::new(
-            context.with_label("game state"),
+        // The game state actors then handle the exchange of game actions, etc. while
+        // driving the GUI actor to output the current state of their games.
+        let llm_metrics = LlmMetrics::default();
+        llm_metrics.register(&context.with_label("llm"));
+        let strategies: StrategyFactory = Box::new(move || -> Box<dyn Strategy> {
+            let model = model.take().unwrap_or_else(|| select_model(&args));
+            Box::new(LlmStrategy::new(
+                model,
+                LlmConfig::default(),
+                llm_metrics.clone(),
+            ))
+        });
+
+        // The GUI shows a tab for the lobby and every started game.
+        let mut gui = None;
+        if !headless {
+            gui = Some(GuiActor::with_tabs(context.with_label("gui"), theme));
+        }
+
+        // The NDJSON writer subscribes to the events of the lobby and all games.
+        let mut events = EventSink::default();
+
+        if headless || events_path.is_some() {
+            let writer: Box<dyn Write + Send> = match &events_path {
+                Some(path) => Box::new(File::create(path).expect("failed to create events file")),
+                None => Box::new(io::stdout()),
+            };
+
+            // NOTE: in headless mode there is no TUI to quit, so the process exits once the first game ended.
+            let (ndjson_actor, ndjson_mailbox) =
+                NdjsonActor::new(context.with_label("events"), writer, headless);
+            ndjson_actor.start();
+            events.subscribe(ndjson_mailbox);
+        }
+
+        // In the lobby mode, the opponents are chosen among the known peers and every
+        // accepted challenge starts another game. Otherwise, a single game is played
+        // against the first allowed peer.
+        let mut lobby_mailbox = None;
+        let mut lobby_board = None;
+        let mut finished = None;
+        let opponents = if lobby || challenge.is_some() || accept_challenges {
+            let mut lobby_events = events.clone();
+            if gui.is_some() {
+                let (sender, receiver) = mpsc::channel(1);
+                lobby_events.subscribe(sender);
+                lobby_board = Some(receiver);
+            }
+
+            // The opponents of the finished games are handed back to the lobby, so that they
+            // can be challenged again.
+            let (finished_sender, finished_receiver) = mpsc::unbounded();
+            finished = Some(finished_sender);
+
+            let (lobby_actor, mailbox, opponents) = LobbyActor::new(
+                context.with_label("lobby"),
+                lobby_events,
+                peers.clone(),
+                policy.clone(),
+                accept_challenges,
+                challenge,
+            );
+            lobby_actor
+                .with_finished(finished_receiver)
+                .start(lobby_sender, lobby_receiver);
+            lobby_mailbox = Some(mailbox);
+
+            opponents
+        } else {
+            let (mut sender, opponents) = mpsc::channel(1);
+            sender
+                .send(peers[0].clone())
+                .await
+                .expect("failed to choose opponent");
+
+            opponents
+        };
+
+        // The GUI sends the commands of the user (e.g. to save the transcript) to the game state
+        // actor of the shown board, and the commands in the lobby to the lobby actor.
+        let mut gui_mailbox = None;
+        if let Some((gui_actor, mut mailbox)) = gui {
+            gui_actor.start(Vec::new(), lobby_mailbox);
+
+            if let Some(events) = lobby_board {
+                mailbox
+                    .add_board(Board {
+                        title: "Lobby".into(),
+                        events,
+                        mailbox: None,
+                    })
+                    .await
+                    .expect("failed to add lobby board");
+            }
+            gui_mailbox = Some(mailbox);
+        }
+
+        // NOTE: the game messages are only sent to the respective opponent.
+        let mut manager = GameManager::new(
+            context.with_label("games"),
+            events,
             gui_mailbox,
+            log_level,
             signer.clone(),
-            model,
-        );
+            strategies,
+            opponents,
+        )
+        .with_policy(policy);
+        if let Some(finished) = finished {
+            manager = manager.with_finished(finished);
+        }
+        if !spectators.is_empty() {
+            let (broadcast_actor, broadcast_mailbox) =
+                BroadcastActor::new(context.with_label("broadcast"), spectators);
+            broadcast_actor.start(spectator_sender, spectator_receiver);
+            manager = manager.with_spectators(broadcast_mailbox);
+        }
+        manager.start(control, moves, chat);
 
-        gamestate_actor.start(gamestate_sender, gamestate_receiver);
-        gui_actor.start();
+        // The runtime is stopped once the user quits the TUI, which ends the process gracefully.
+        let network_handle = network.start();
 
-        network.start().await.expect("Network failed");
+        select! {
+            result = network_handle => {
+                result.expect("Network failed");
+            },
+            _ = context.stopped() => {},
+        }
     });
 }
+
+/// Selects the LLM model to compute the moves, based on the passed CLI arguments.
+///
+/// The mock model either replays the responses from the given file
+/// or returns random moves with the configured rate of malformed responses.
+fn select_model(args: &clap::ArgMatches) -> Box<dyn Model> {
+    let name = args
+        .get_one::<String>("model")
+        .expect("must provide --model")
+        .to_lowercase();
+
+    if name == "mock" {
+        if let Some(filepath) = args.get_one::<String>("mock-responses") {
+            return Box::new(MockModel::from_file(filepath).expect("failed to read mock responses"));
+        }
+
+        let malformed_rate = args
+            .get_one::<String>("mock-malformed-rate")
+            .expect("must provide --mock-malformed-rate")
+            .parse::<f64>()
+            .expect("invalid malformed rate");
+
+        return Box::new(MockModel::random(malformed_rate, fastrand::u64(..)));
+    }
+
+    find_model(&name).expect("failed to find llm")
+}
@@ -0,0 +1,136 @@
+/// This binary runs a game between two players in a single process.
+///
+/// Both game state actors are connected through the simulated p2p network
+/// of the Commonware framework, so that no configuration or keys are required.
+/// The boards of both players are rendered side by side in the TUI.
+use std::time::Duration;
+
+use battleship_commonware::{
+    application::{ChannelSender, actor::GameStateActor},
+    events::EventSink,
+    gui::{GuiActor, LogLevel, Theme},
+    strategy::{self, LlmMetrics, Strategy},
+};
+
+use clap::arg;
+use commonware_cryptography::{PrivateKeyExt as _, Signer, ed25519::PrivateKey};
+use commonware_p2p::simulated::{self, Link, Network};
+use commonware_runtime::{Metrics, Runner, Spawner, tokio};
+
+const MAX_MESSAGE_SIZE: usize = 1024;
+
+fn main() {
+    let command = clap::Command::new("battleship-commonware-selfplay").args([
+        arg!(--first [STRATEGY] "the strategy of the first player (e.g. 'hunt', 'density', 'mock' or 'llm:<model>')")
+            .default_value("hunt"),
+        arg!(--second [STRATEGY] "the strategy of the second player (e.g. 'hunt', 'density', 'mock' or 'llm:<model>')")
+            .default_value("mock"),
+        arg!(--theme [THEME] "the color theme of the TUI ('default' or 'colorblind')")
+            .default_value("default"),
+        arg!(--"log-level" [LEVEL] "the minimum level of the shown logs ('debug', 'info' or 'error')")
+            .default_value("info"),
+    ]);
+
+    let args = command.get_matches();
+    let strategies = ["first", "second"].map(|id| {
+        args.get_one::<String>(id)
+            .expect("must provide strategy")
+            .to_lowercase()
+    });
+    let theme = Theme::from_name(args.get_one::<String>("theme").expect("must provide --theme"))
+        .expect("invalid theme");
+    let log_level = args
+        .get_one::<String>("log-level")
+        .expect("must provide --log-level")
+        .parse::<LogLevel>()
+        .expect("invalid log level");
+
+    let runner_config = tokio::Config::new().with_read_write_timeout(Duration::from_secs(10));
+    let executor = tokio::Runner::new(runner_config);
+
+    executor.start(|context| async move {
+        let (network, mut oracle) = Network::new(
+            context.with_label("network"),
+            simulated::Config {
+                max_size: MAX_MESSAGE_SIZE,
+                disconnect_on_block: true,
+                tracked_peer_sets: None,
+            },
+        );
+        network.start();
+
+        let signers = [PrivateKey::from_seed(0), PrivateKey::from_seed(1)];
+
+        // Both players are linked in both directions with a small latency
+        // to resemble a local network connection.
+        for (from, to) in [(0, 1), (1, 0)] {
+            oracle
+                .add_link(
+                    signers[from].public_key(),
+                    signers[to].public_key(),
+                    Link {
+                        latency: Duration::from_millis(10),
+                        jitter: Duration::from_millis(1),
+                        success_rate: 1.0,
+                    },
+                )
+                .await
+                .expect("failed to add link");
+        }
+
+        let (gui_actor, gui_mailboxes) = GuiActor::with_boards(context.with_label("gui"), 2, theme);
+
+        // The game state actors are created before starting the GUI,
+        // since the GUI sends the commands of the user to their mailboxes.
+        let mut actors = Vec::new();
+        let mut mailboxes = Vec::new();
+        let opponents = [signers[1].public_key(), signers[0].public_key()];
+        for (id, ((signer, opponent), gui_mailbox)) in signers
+            .into_iter()
+            .zip(opponents)
+            .zip(gui_mailboxes)
+            .enumerate()
+        {
+            let (sender, receiver) = oracle
+                .control(signer.public_key())
+                .register(0)
+                .await
+                .expect("failed to register channel");
+
+            let player_context = context.with_label(&format!("player_{}", id));
+            let strategy = build_strategy(&strategies[id], &player_context);
+            let (gamestate_actor, mailbox) = GameStateActor::new(
+                player_context,
+                EventSink::new(vec![gui_mailbox]),
+                log_level,
+                signer,
+                opponent,
+                strategy,
+            );
+
+            // NOTE: the simulated network has no quotas, so all messages are sent over a single channel.
+            actors.push((gamestate_actor, ChannelSender::single(sender), receiver));
+            mailboxes.push(mailbox);
+        }
+
+        gui_actor.start(mailboxes, None);
+
+        // NOTE: the first turn is decided by a coin flip during the handshake,
+        // so both players can be started at the same time.
+        for (gamestate_actor, sender, receiver) in actors {
+            gamestate_actor.start(sender, receiver);
+        }
+
+        // The actors are running in the background, so we're keeping the runtime alive
+        // until the user quits the TUI.
+        let _ = context.stopped().await;
+    });
+}
+
+/// Builds the strategy with the given name, registering the LLM metrics with the given context.
+fn build_strategy(name: &str, context: &impl Metrics) -> Box<dyn Strategy> {
+    let metrics = LlmMetrics::default();
+    metrics.register(&context.with_label("llm"));
+
+    strategy::from_name(name, fastrand::u64(..), &metrics).expect("failed to build strategy")
+}
@@ -1,8 +1,10 @@
 /// This binary prepares the testing setup for two parties that can be
 /// playing the battleship game.
-use battleship_commonware::config::{get_config_path, parse_private_key};
+use battleship_commonware::config::{
+    ChannelPreset, NetworkMode, Peer, get_config_path, parse_private_key,
+};
 
-use clap::{Command, arg};
+use clap::{ArgAction, Command, arg};
 use commonware_cryptography::Signer;
 
 fn main() {
@@ -11,8 +13,27 @@ fn main() {
     let command = Command::new("battleship-commonware-setup").args([
         arg!(--"private-key" <PK> "the private key to use for this player"),
         arg!(--port <PORT> "the network port to use for this player"),
-        arg!(--"peer-endpoint" <PEER_ENDPOINT> "the peer to connect with for the game"),
-        arg!(--"peer-public-key" <PEER_PK> "the other player's public key"),
+        arg!(--"peer-endpoint" <PEER_ENDPOINT> "the endpoint of a known peer (can be repeated)")
+            .action(ArgAction::Append),
+        arg!(--"peer-public-key" <PEER_PK> "the public key of a known peer, in the same order as the endpoints")
+            .action(ArgAction::Append),
+        arg!(--spectator [SPECTATOR_PK] "the public key of a spectator allowed to watch the games (can be repeated)")
+            .action(ArgAction::Append),
+        arg!(--allow [PEER_PK] "the public key of a peer allowed as opponent; all known peers are allowed if not set (can be repeated)")
+            .action(ArgAction::Append),
+        arg!(--block [PEER_PK] "the public key of a peer that's never accepted as opponent (can be repeated)")
+            .action(ArgAction::Append),
+        arg!(--"single-opponent" "only play against a single opponent, declining all other challenges"),
+        arg!(--listen [ADDR] "the address to listen on (defaults to all interfaces on the port)"),
+        arg!(--dialable [ADDR] "the address at which other hosts can dial this player (defaults to localhost on the port)"),
+        arg!(--production "use the production preset of the p2p network, e.g. for players on different hosts"),
+        arg!(--"allow-private-ips" "accept peers with private addresses in the production preset, e.g. on a LAN"),
+        arg!(--"bootstrapper-endpoint" [ENDPOINT] "the endpoint of an additional bootstrapper (can be repeated)")
+            .action(ArgAction::Append),
+        arg!(--"bootstrapper-public-key" [PK] "the public key of an additional bootstrapper, in the same order as the endpoints")
+            .action(ArgAction::Append),
+        arg!(--"channel-preset" [PRESET] "the preset of the channel quotas ('interactive' for the TUI or 'bot' for headless bots)")
+            .default_value("interactive"),
     ]);
 
     let args = command.get_matches();
@@ -28,16 +49,74 @@ fn main() {
         .parse::<u16>()
         .expect("invalid port");
 
-    let peer_endpoint = args
-        .get_one::<String>("peer-endpoint")
-        .expect("must set --peer-endpoint");
+    let peer_endpoints = args
+        .get_many::<String>("peer-endpoint")
+        .expect("must set --peer-endpoint")
+        .collect::<Vec<&String>>();
 
-    let peer_public_key = args
-        .get_one::<String>("peer-public-key")
-        .expect("must set --peer-public-key");
+    let peer_public_keys = args
+        .get_many::<String>("peer-public-key")
+        .expect("must set --peer-public-key")
+        .collect::<Vec<&String>>();
 
-    let config =
-        battleship_commonware::Config::new(&private_key, port, peer_endpoint, peer_public_key);
+    assert_eq!(
+        peer_endpoints.len(),
+        peer_public_keys.len(),
+        "must set one --peer-public-key per --peer-endpoint"
+    );
+    let peers = peer_endpoints
+        .into_iter()
+        .zip(peer_public_keys)
+        .map(|(endpoint, public_key)| Peer::new(endpoint, public_key))
+        .collect();
+
+    let mut config = battleship_commonware::Config::new(&private_key, port, peers);
+    config.spectators = args
+        .get_many::<String>("spectator")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    config.policy.allowlist = args
+        .get_many::<String>("allow")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    config.policy.blocklist = args
+        .get_many::<String>("block")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    config.policy.single_opponent = args.get_flag("single-opponent");
+
+    let bootstrapper_endpoints = args
+        .get_many::<String>("bootstrapper-endpoint")
+        .unwrap_or_default()
+        .collect::<Vec<&String>>();
+    let bootstrapper_public_keys = args
+        .get_many::<String>("bootstrapper-public-key")
+        .unwrap_or_default()
+        .collect::<Vec<&String>>();
+    assert_eq!(
+        bootstrapper_endpoints.len(),
+        bootstrapper_public_keys.len(),
+        "must set one --bootstrapper-public-key per --bootstrapper-endpoint"
+    );
+    config.network.bootstrappers = bootstrapper_endpoints
+        .into_iter()
+        .zip(bootstrapper_public_keys)
+        .map(|(endpoint, public_key)| Peer::new(endpoint, public_key))
+        .collect();
+    config.network.listen = args.get_one::<String>("listen").cloned();
+    config.network.dialable = args.get_one::<String>("dialable").cloned();
+    if args.get_flag("production") {
+        config.network.mode = NetworkMode::Production;
+    }
+    config.network.allow_private_ips = args.get_flag("allow-private-ips");
+    config.channels.preset = args
+        .get_one::<String>("channel-preset")
+        .expect("must set --channel-preset")
+        .parse::<ChannelPreset>()
+        .expect("invalid channel preset");
     config.validate().expect("invalid config");
 
     config
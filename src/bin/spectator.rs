@@ -0,0 +1,99 @@
+/// This binary runs a spectator node, which watches the games of the configured players live.
+///
+/// The spectator's configuration lists the players as its known peers, while the players
+/// have to allow the spectator's public key in their own configurations.
+/// The games are rendered read-only, with a tab for every watched game.
+use std::time::Duration;
+
+use battleship_commonware::{
+    Config,
+    config::{SPECTATOR_CHANNEL, parse_public_key},
+    get_config_path,
+    gui::{GuiActor, Theme},
+    spectator::SpectatorActor,
+};
+
+use clap::arg;
+use commonware_macros::select;
+use commonware_p2p::{Manager, authenticated::discovery};
+use commonware_runtime::{Metrics, Runner, Spawner, tokio};
+
+fn main() {
+    let command = clap::Command::new("battleship-commonware-spectator").args([
+        arg!(--"public-key" <PUBKEY> "the spectator's public key"),
+        arg!(--theme [THEME] "the color theme of the TUI ('default' or 'colorblind')")
+            .default_value("default"),
+    ]);
+
+    let args = command.get_matches();
+    let public_key = parse_public_key(
+        args.get_one::<String>("public-key")
+            .expect("must provide --public-key"),
+    )
+    .expect("invalid public key");
+    let theme = Theme::from_name(
+        args.get_one::<String>("theme")
+            .expect("must provide --theme"),
+    )
+    .expect("invalid theme");
+
+    let config = Config::read(&get_config_path(&public_key)).expect("failed to read config");
+    config.validate().expect("invalid config");
+
+    // The players and the additional bootstrappers are used to bootstrap the connection
+    // to the p2p network.
+    let players = config
+        .get_peers()
+        .expect("invalid peers")
+        .into_iter()
+        .map(|(public_key, _)| public_key)
+        .collect::<Vec<_>>();
+    let authorized = players
+        .iter()
+        .cloned()
+        .chain(
+            config
+                .get_bootstrappers()
+                .expect("invalid bootstrappers")
+                .into_iter()
+                .map(|(public_key, _)| public_key),
+        )
+        .collect::<Vec<_>>();
+    let p2p_config = config.get_p2p_config().expect("invalid network config");
+    let channels = config.get_channels().expect("invalid channels");
+
+    let runner_config = tokio::Config::new().with_read_write_timeout(Duration::from_secs(10));
+    let executor = tokio::Runner::new(runner_config);
+
+    executor.start(|context| async move {
+        let (mut network, mut oracle) =
+            discovery::Network::new(context.with_label("network"), p2p_config);
+        oracle.update(0, authorized.into()).await;
+
+        // NOTE: the spectator only registers the channel of the spectators, so the messages
+        // of the games and the lobby are never received.
+        let (sender, receiver) = network.register(
+            SPECTATOR_CHANNEL,
+            channels.spectators.quota(),
+            channels.spectators.backlog,
+        );
+
+        // The GUI shows a tab for every watched game, without sending any commands to the players.
+        let (gui_actor, gui_mailbox) = GuiActor::with_tabs(context.with_label("gui"), theme);
+        gui_actor.start(Vec::new(), None);
+
+        let spectator_actor =
+            SpectatorActor::new(context.with_label("spectator"), players, gui_mailbox);
+        spectator_actor.start(sender, receiver);
+
+        // The runtime is stopped once the user quits the TUI, which ends the process gracefully.
+        let network_handle = network.start();
+
+        select! {
+            result = network_handle => {
+                result.expect("Network failed");
+            },
+            _ = context.stopped() => {},
+        }
+    });
+}
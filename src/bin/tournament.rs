@@ -0,0 +1,54 @@
+/// This binary runs a headless tournament between the registered strategies.
+///
+/// All games are played on the deterministic runtime, so that running the
+/// tournament with the same seed yields the same results.
+use battleship_commonware::tournament::{self, TournamentConfig};
+
+use clap::arg;
+use commonware_runtime::{Runner, deterministic};
+
+fn main() {
+    let command = clap::Command::new("battleship-commonware-tournament").args([
+        arg!(--strategies [STRATEGIES] "comma-separated list of the competing strategies")
+            .default_value("hunt,random,density"),
+        arg!(--games [GAMES] "the number of games between every pair of strategies")
+            .default_value("1000"),
+        arg!(--seed [SEED] "the seed of the deterministic runtime").default_value("0"),
+        arg!(--json [FILE] "file to export the results as JSON to"),
+    ]);
+
+    let args = command.get_matches();
+    let strategies = args
+        .get_one::<String>("strategies")
+        .expect("must provide --strategies")
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<String>>();
+    assert!(strategies.len() > 1, "must provide at least two strategies");
+
+    let games = args
+        .get_one::<String>("games")
+        .expect("must provide --games")
+        .parse::<usize>()
+        .expect("invalid number of games");
+
+    let seed = args
+        .get_one::<String>("seed")
+        .expect("must provide --seed")
+        .parse::<u64>()
+        .expect("invalid seed");
+
+    let config = TournamentConfig { strategies, games };
+    let executor = deterministic::Runner::seeded(seed);
+    let report = executor.start(|mut context| async move {
+        tournament::run(&mut context, &config).expect("failed to run tournament")
+    });
+
+    print!("{}", report);
+
+    if let Some(filepath) = args.get_one::<String>("json") {
+        let json = serde_json::to_string_pretty(&report).expect("failed to serialize report");
+        std::fs::write(filepath, json).expect("failed to export report");
+    }
+}
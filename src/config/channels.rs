@@ -0,0 +1,231 @@
+use std::num::NonZeroU32;
+
+use governor::Quota;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{CHAT_LIMIT, Envelope},
+    spectator,
+};
+
+use super::Config;
+
+/// The channel of the session control messages (e.g. the handshake, the rematch and the acknowledgements).
+pub const CONTROL_CHANNEL: u64 = 0;
+/// The channel of the lobby.
+pub const LOBBY_CHANNEL: u64 = 1;
+/// The channel of the spectators.
+pub const SPECTATOR_CHANNEL: u64 = 2;
+/// The channel of the moves of the games.
+pub const MOVES_CHANNEL: u64 = 3;
+/// The channel of the chat between the players.
+pub const CHAT_CHANNEL: u64 = 4;
+
+/// The presets of the channel quotas and the maximum message size, per game mode.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelPreset {
+    /// The preset for games in the TUI, where the moves are made at a human or LLM pace.
+    #[default]
+    Interactive,
+    /// The preset for headless bots, which make their moves as fast as possible.
+    Bot,
+}
+
+impl ChannelPreset {
+    /// Returns the channels of the preset.
+    ///
+    /// NOTE: the chat channel accepts a burst of the chat messages the rate limit of the chat allows,
+    /// since the chat messages are not retransmitted if they're throttled.
+    pub fn channels(&self) -> Channels {
+        let chat = ChannelConfig::new(CHAT_LIMIT as u32, 8);
+        match self {
+            ChannelPreset::Interactive => Channels {
+                max_message_size: 8192,
+                control: ChannelConfig::new(4, 32),
+                moves: ChannelConfig::new(2, 16),
+                chat,
+                lobby: ChannelConfig::new(4, 16),
+                spectators: ChannelConfig::new(4, 16),
+            },
+            ChannelPreset::Bot => Channels {
+                max_message_size: 8192,
+                control: ChannelConfig::new(32, 64),
+                moves: ChannelConfig::new(32, 64),
+                chat,
+                lobby: ChannelConfig::new(4, 16),
+                spectators: ChannelConfig::new(32, 64),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for ChannelPreset {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "interactive" => Ok(Self::Interactive),
+            "bot" => Ok(Self::Bot),
+            _ => Err(eyre::eyre!("unknown channel preset: {}", s)),
+        }
+    }
+}
+
+/// The rate limit and the backlog of a registered channel of the p2p network.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ChannelConfig {
+    /// The number of messages per second that are accepted from every peer.
+    pub rate: u32,
+    /// The number of received messages that are buffered until they're handled.
+    pub backlog: usize,
+}
+
+impl ChannelConfig {
+    pub fn new(rate: u32, backlog: usize) -> Self {
+        Self { rate, backlog }
+    }
+
+    /// Returns the rate limit of the channel.
+    pub fn quota(&self) -> Quota {
+        Quota::per_second(NonZeroU32::new(self.rate).expect("rate must be positive"))
+    }
+}
+
+/// The configuration of the channels, where every unset value is taken from the preset.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ChannelsConfig {
+    /// The preset of the game mode, which the unset values are taken from.
+    #[serde(default)]
+    pub preset: ChannelPreset,
+    /// The maximum size of every message sent over the p2p network.
+    #[serde(default)]
+    pub max_message_size: Option<usize>,
+    /// The channel of the session control messages.
+    #[serde(default)]
+    pub control: Option<ChannelConfig>,
+    /// The channel of the moves.
+    #[serde(default)]
+    pub moves: Option<ChannelConfig>,
+    /// The channel of the chat.
+    #[serde(default)]
+    pub chat: Option<ChannelConfig>,
+    /// The channel of the lobby.
+    #[serde(default)]
+    pub lobby: Option<ChannelConfig>,
+    /// The channel of the spectators.
+    #[serde(default)]
+    pub spectators: Option<ChannelConfig>,
+}
+
+/// The channels of the p2p network, as used by the player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Channels {
+    /// The maximum size of every message sent over the p2p network.
+    pub max_message_size: usize,
+    /// The channel of the session control messages (e.g. the handshake, the rematch and the acknowledgements).
+    ///
+    /// NOTE: every move is acknowledged over this channel, while the chat messages are not acknowledged.
+    pub control: ChannelConfig,
+    /// The channel of the moves of the games.
+    pub moves: ChannelConfig,
+    /// The channel of the chat between the players.
+    pub chat: ChannelConfig,
+    /// The channel of the lobby.
+    pub lobby: ChannelConfig,
+    /// The channel of the spectators.
+    pub spectators: ChannelConfig,
+}
+
+impl Channels {
+    /// Validates that every channel accepts messages and that the largest encodable message
+    /// fits the maximum message size.
+    pub fn validate(&self) -> eyre::Result<()> {
+        for (name, channel) in [
+            ("control", self.control),
+            ("moves", self.moves),
+            ("chat", self.chat),
+            ("lobby", self.lobby),
+            ("spectators", self.spectators),
+        ] {
+            if channel.rate == 0 || channel.backlog == 0 {
+                return Err(eyre::eyre!(
+                    "rate and backlog of the {} channel must be positive",
+                    name
+                ));
+            }
+        }
+
+        let largest = Envelope::max_encoded_size().max(spectator::Message::max_encoded_size());
+        if largest > self.max_message_size {
+            return Err(eyre::eyre!(
+                "maximum message size must be at least {} bytes",
+                largest
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Returns the channels of the p2p network, where the configured values override the preset.
+    pub fn get_channels(&self) -> eyre::Result<Channels> {
+        let preset = self.channels.preset.channels();
+        let channels = Channels {
+            max_message_size: self
+                .channels
+                .max_message_size
+                .unwrap_or(preset.max_message_size),
+            control: self.channels.control.unwrap_or(preset.control),
+            moves: self.channels.moves.unwrap_or(preset.moves),
+            chat: self.channels.chat.unwrap_or(preset.chat),
+            lobby: self.channels.lobby.unwrap_or(preset.lobby),
+            spectators: self.channels.spectators.unwrap_or(preset.spectators),
+        };
+        channels.validate()?;
+
+        Ok(channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commonware_cryptography::{PrivateKeyExt as _, ed25519::PrivateKey};
+
+    #[test]
+    fn test_presets() {
+        for preset in [ChannelPreset::Interactive, ChannelPreset::Bot] {
+            assert!(preset.channels().validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_get_channels() {
+        let mut config = Config::new(&PrivateKey::from_seed(0), 5670, Vec::new());
+        assert_eq!(
+            ChannelPreset::Interactive.channels(),
+            config.get_channels().unwrap()
+        );
+
+        config.channels.preset = ChannelPreset::Bot;
+        config.channels.moves = Some(ChannelConfig::new(8, 8));
+        let channels = config.get_channels().unwrap();
+        assert_eq!(ChannelConfig::new(8, 8), channels.moves);
+        assert_eq!(ChannelPreset::Bot.channels().lobby, channels.lobby);
+
+        // the messages must be accepted by every channel
+        config.channels.lobby = Some(ChannelConfig::new(0, 16));
+        assert!(config.get_channels().is_err());
+        config.channels.lobby = None;
+
+        // the largest message must fit the maximum message size
+        let largest = Envelope::max_encoded_size().max(spectator::Message::max_encoded_size());
+        config.channels.max_message_size = Some(largest - 1);
+        assert!(config.get_channels().is_err());
+        config.channels.max_message_size = Some(largest);
+        assert!(config.get_channels().is_ok());
+    }
+}
@@ -8,26 +8,87 @@ use commonware_cryptography::{
 use commonware_utils::from_hex_formatted;
 use serde::{Deserialize, Serialize};
 
+use crate::lobby::Policy;
+
+mod channels;
+mod network;
+
+pub use channels::{
+    CHAT_CHANNEL, CONTROL_CHANNEL, ChannelConfig, ChannelPreset, Channels, ChannelsConfig,
+    LOBBY_CHANNEL, MOVES_CHANNEL, SPECTATOR_CHANNEL,
+};
+pub use network::{NAMESPACE, NetworkConfig, NetworkMode};
+
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct Config {
     private_key: String,
     pub port: u16,
-    pub peer_endpoint: String,
-    pub peer_public_key: String,
+    /// The known peers, which can be challenged to a game in the lobby.
+    pub peers: Vec<Peer>,
+    /// The public keys of the spectators, which are allowed to watch the games.
+    #[serde(default)]
+    pub spectators: Vec<String>,
+    /// The policy which of the known peers are accepted as opponents.
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    /// The addresses of the node in the p2p network.
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// The quotas of the channels and the maximum message size of the p2p network.
+    #[serde(default)]
+    pub channels: ChannelsConfig,
+}
+
+/// The configured policy which of the known peers are accepted as opponents.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct PolicyConfig {
+    /// The public keys of the peers that are allowed as opponents; all known peers are allowed if empty.
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// The public keys of the peers that are never accepted as opponents.
+    #[serde(default)]
+    pub blocklist: Vec<String>,
+    /// Signals if only a single opponent is played against.
+    #[serde(default)]
+    pub single_opponent: bool,
+}
+
+/// A known peer, which is used to bootstrap the connection to the p2p network.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Peer {
+    pub endpoint: String,
+    pub public_key: String,
+}
+
+impl Peer {
+    pub fn new(endpoint: &str, public_key: &str) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            public_key: public_key.into(),
+        }
+    }
+
+    /// Parses the peer's public key.
+    pub fn get_public_key(&self) -> eyre::Result<PublicKey> {
+        parse_public_key(&self.public_key)
+    }
+
+    /// Parses the peer's endpoint.
+    pub fn get_endpoint(&self) -> eyre::Result<SocketAddr> {
+        parse_socket_addr(&self.endpoint)
+    }
 }
 
 impl Config {
-    pub fn new(
-        private_key: &PrivateKey,
-        port: u16,
-        peer_endpoint: &str,
-        peer_public_key: &str,
-    ) -> Self {
+    pub fn new(private_key: &PrivateKey, port: u16, peers: Vec<Peer>) -> Self {
         Self {
             private_key: private_key.to_string(),
             port,
-            peer_endpoint: peer_endpoint.into(),
-            peer_public_key: peer_public_key.into(),
+            peers,
+            spectators: Vec::new(),
+            policy: PolicyConfig::default(),
+            network: NetworkConfig::default(),
+            channels: ChannelsConfig::default(),
         }
     }
 
@@ -62,27 +123,141 @@ impl Config {
     }
 
     /// Retrieve a configuration stored in a given filepath.
+    ///
+    /// Configurations of previous versions with a single peer are migrated to the list of known peers.
     pub fn read(filepath: &str) -> eyre::Result<Self> {
         let contents = std::fs::read_to_string(std::path::Path::new(filepath))?;
-        let config: Config = serde_yaml::from_str(&contents)?;
+        let mut value: serde_yaml::Value = serde_yaml::from_str(&contents)?;
+        migrate_single_peer(&mut value)?;
 
-        Ok(config)
+        serde_yaml::from_value(value).map_err(|e| {
+            eyre::eyre!(
+                "invalid config {}: {}; rerun the setup to create a new one",
+                filepath,
+                e
+            )
+        })
     }
 
     pub fn validate(&self) -> eyre::Result<()> {
-        let _ = parse_private_key(&self.private_key)?;
-        let _ = parse_public_key(&self.peer_public_key)?;
-        let _ = parse_socket_addr(&self.peer_endpoint)?;
+        let public_key = parse_private_key(&self.private_key)?.public_key();
+        if self.peers.is_empty() {
+            return Err(eyre::eyre!("at least one peer must be configured"));
+        }
 
-        Ok(())
+        let mut public_keys = Vec::new();
+        for peer in &self.peers {
+            let peer_public_key = peer.get_public_key()?;
+            let _ = peer.get_endpoint()?;
+
+            if peer_public_key == public_key {
+                return Err(eyre::eyre!("own public key can't be a peer"));
+            }
+            if public_keys.contains(&peer_public_key) {
+                return Err(eyre::eyre!("duplicate peer: {}", peer.public_key));
+            }
+            public_keys.push(peer_public_key);
+        }
+
+        for spectator in self.get_spectators()? {
+            if spectator == public_key || public_keys.contains(&spectator) {
+                return Err(eyre::eyre!("spectator can't be a player: {}", spectator));
+            }
+        }
+
+        let policy = self.get_policy()?;
+        if !public_keys.iter().any(|peer| policy.allows(peer)) {
+            return Err(eyre::eyre!("no known peer is allowed by the policy"));
+        }
+
+        let _ = self.get_channels()?;
+        self.validate_network()
+    }
+
+    /// Returns the parsed public keys and endpoints of all known peers.
+    pub fn get_peers(&self) -> eyre::Result<Vec<(PublicKey, SocketAddr)>> {
+        self.peers
+            .iter()
+            .map(|peer| Ok((peer.get_public_key()?, peer.get_endpoint()?)))
+            .collect()
+    }
+
+    /// Returns the parsed public keys of all spectators.
+    pub fn get_spectators(&self) -> eyre::Result<Vec<PublicKey>> {
+        self.spectators
+            .iter()
+            .map(|spectator| parse_public_key(spectator))
+            .collect()
+    }
+
+    /// Returns the parsed policy which of the known peers are accepted as opponents.
+    pub fn get_policy(&self) -> eyre::Result<Policy<PublicKey>> {
+        let parse = |public_keys: &[String]| {
+            public_keys
+                .iter()
+                .map(|public_key| parse_public_key(public_key))
+                .collect::<eyre::Result<Vec<_>>>()
+        };
+
+        let allowlist = parse(&self.policy.allowlist)?;
+        Ok(Policy::new(
+            (!allowlist.is_empty()).then_some(allowlist),
+            parse(&self.policy.blocklist)?,
+            self.policy.single_opponent,
+        ))
     }
 }
 
+/// Migrates the single peer of a previous version (`peer_endpoint` and `peer_public_key`)
+/// to the list of known peers.
+fn migrate_single_peer(value: &mut serde_yaml::Value) -> eyre::Result<()> {
+    let Some(mapping) = value.as_mapping_mut() else {
+        return Ok(());
+    };
+
+    let endpoint = mapping.remove("peer_endpoint");
+    let public_key = mapping.remove("peer_public_key");
+    let peer = match (&endpoint, &public_key) {
+        (None, None) => return Ok(()),
+        (Some(endpoint), Some(public_key)) if !mapping.contains_key("peers") => {
+            endpoint.as_str().zip(public_key.as_str())
+        }
+        _ => None,
+    };
+    let Some((endpoint, public_key)) = peer else {
+        return Err(eyre::eyre!(
+            "invalid single peer config; rerun the setup to create a new one"
+        ));
+    };
+
+    mapping.insert(
+        "peers".into(),
+        serde_yaml::to_value(vec![Peer::new(endpoint, public_key)])?,
+    );
+
+    Ok(())
+}
+
 /// Builds the configuration file path for the given player ID.
 pub fn get_config_path(public_key: &PublicKey) -> String {
     format!("./.battleship-commonware/config-{}.yaml", public_key)
 }
 
+/// Builds the path of the directory, in which all data of the session with the given ID is stored.
+pub fn get_session_dir(session: &str) -> String {
+    format!("./.battleship-commonware/sessions/{}", session)
+}
+
+/// Builds the path to store the transcript of the given game of a session from the given player's view.
+pub fn get_transcript_path(session: &str, game: u32, player: &str) -> String {
+    format!(
+        "{}/transcript-{}-{}.json",
+        get_session_dir(session),
+        game,
+        player
+    )
+}
+
 /// Parses a hex-formatted ed25510 public key.
 pub fn parse_public_key(input: &str) -> eyre::Result<PublicKey> {
     let public_key_bytes = from_hex_formatted(input).unwrap_or_default();
@@ -116,34 +291,70 @@ mod tests {
         let config = Config::new(
             &PrivateKey::from_seed(0),
             5670,
-            "127.0.0.1:5671".into(),
-            "9a3744504560639ec670b7a17d492b273e077b0a96bef58ba7760779e544546e".into(),
+            vec![
+                Peer::new(
+                    "127.0.0.1:5671",
+                    &PrivateKey::from_seed(1).public_key().to_string(),
+                ),
+                Peer::new(
+                    "127.0.0.1:5672",
+                    &PrivateKey::from_seed(2).public_key().to_string(),
+                ),
+            ],
         );
 
         assert!(!config.private_key.is_empty());
-        assert_eq!(config.peer_endpoint, "127.0.0.1:5671");
-        assert!(!config.peer_public_key.is_empty());
+        assert_eq!(config.peers[0].endpoint, "127.0.0.1:5671");
+        assert_eq!(2, config.get_peers().unwrap().len());
         assert!(config.validate().is_ok());
     }
 
     #[test]
     fn test_invalid_config() {
-        assert!(
-            !Config::new(
-                &PrivateKey::from_seed(0),
-                5670,
-                "abc",
-                "9a3744504560639ec670b7a17d492b273e077b0a96bef58ba7760779e544546e",
-            )
-            .validate()
-            .is_ok()
-        );
-
-        assert!(
-            !Config::new(&PrivateKey::from_seed(0), 5671, "127.0.0.1:5670", "hij0123",)
+        let peer = PrivateKey::from_seed(1).public_key().to_string();
+        let invalid = |peers: Vec<Peer>| {
+            !Config::new(&PrivateKey::from_seed(0), 5670, peers)
                 .validate()
                 .is_ok()
+        };
+
+        assert!(invalid(vec![Peer::new("abc", &peer)]));
+        assert!(invalid(vec![Peer::new("127.0.0.1:5671", "hij0123")]));
+        assert!(invalid(vec![]));
+        assert!(invalid(vec![
+            Peer::new("127.0.0.1:5671", &peer),
+            Peer::new("127.0.0.1:5672", &peer),
+        ]));
+        assert!(invalid(vec![Peer::new(
+            "127.0.0.1:5671",
+            &PrivateKey::from_seed(0).public_key().to_string()
+        )]));
+
+        // spectators can't be players at the same time
+        let mut config = Config::new(
+            &PrivateKey::from_seed(0),
+            5670,
+            vec![Peer::new("127.0.0.1:5671", &peer)],
         );
+        config.spectators = vec![PrivateKey::from_seed(2).public_key().to_string()];
+        assert!(config.validate().is_ok());
+        config.spectators.push(peer.clone());
+        assert!(config.validate().is_err());
+
+        // at least one known peer must be allowed by the policy
+        let mut config = Config::new(
+            &PrivateKey::from_seed(0),
+            5670,
+            vec![Peer::new("127.0.0.1:5671", &peer)],
+        );
+        config.policy.allowlist = vec![PrivateKey::from_seed(2).public_key().to_string()];
+        assert!(config.validate().is_err());
+        config.policy.allowlist.push(peer.clone());
+        assert!(config.validate().is_ok());
+        config.policy.blocklist = vec![peer];
+        assert!(config.validate().is_err());
+        config.policy.blocklist = vec!["hij0123".into()];
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -151,8 +362,10 @@ mod tests {
         let config = Config::new(
             &PrivateKey::from_seed(0),
             5670,
-            "127.0.0.1:5671".into(),
-            "9a3744504560639ec670b7a17d492b273e077b0a96bef58ba7760779e544546e".into(),
+            vec![Peer::new(
+                "127.0.0.1:5671",
+                "9a3744504560639ec670b7a17d492b273e077b0a96bef58ba7760779e544546e",
+            )],
         );
 
         let filepath = NamedTempFile::new().expect("failed to get temporary filename");
@@ -162,4 +375,26 @@ mod tests {
         let read_config = Config::read(path_string).expect("failed to read config");
         assert_eq!(config, read_config);
     }
+
+    #[test]
+    fn test_read_single_peer_config() {
+        let peer = PrivateKey::from_seed(1).public_key().to_string();
+        let contents = format!(
+            "private_key: '{}'\nport: 5670\npeer_endpoint: 127.0.0.1:5671\npeer_public_key: '{}'\n",
+            PrivateKey::from_seed(0),
+            peer
+        );
+
+        let filepath = NamedTempFile::new().expect("failed to get temporary filename");
+        let path_string = &filepath.path().to_string_lossy();
+        std::fs::write(filepath.path(), &contents).expect("failed to write config");
+        let config = Config::read(path_string).expect("failed to read config");
+        assert_eq!(vec![Peer::new("127.0.0.1:5671", &peer)], config.peers);
+        assert!(config.validate().is_ok());
+
+        // a partial single peer can't be migrated
+        let contents = contents.replace("peer_endpoint", "endpoint");
+        std::fs::write(filepath.path(), contents).expect("failed to write config");
+        assert!(Config::read(path_string).is_err());
+    }
 }
@@ -0,0 +1,301 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use commonware_cryptography::ed25519::{PrivateKey, PublicKey};
+use commonware_p2p::authenticated::discovery;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, Peer, parse_socket_addr};
+
+/// The namespace of the p2p network, which is shared by all players and spectators.
+pub const NAMESPACE: &[u8] = b"BATTLESHIP_NAMESPACE";
+
+/// The presets of the discovery config of the p2p network.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkMode {
+    /// The preset for nodes on the same host, e.g. for testing.
+    #[default]
+    Local,
+    /// The preset for nodes on different hosts.
+    Production,
+}
+
+/// The configuration of the node's addresses in the p2p network.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct NetworkConfig {
+    /// The preset of the discovery config.
+    #[serde(default)]
+    pub mode: NetworkMode,
+    /// The address to listen on for incoming connections, which defaults to all interfaces on the configured port.
+    #[serde(default)]
+    pub listen: Option<String>,
+    /// The address at which the other peers can dial the node, which defaults to localhost on the configured port.
+    #[serde(default)]
+    pub dialable: Option<String>,
+    /// Additional nodes to bootstrap the connection to the p2p network, besides the known peers.
+    ///
+    /// NOTE: these are not accepted as opponents, but only used to discover the known peers.
+    #[serde(default)]
+    pub bootstrappers: Vec<Peer>,
+    /// Signals if peers with private addresses are accepted in the production mode, e.g. on a LAN.
+    #[serde(default)]
+    pub allow_private_ips: bool,
+}
+
+impl Config {
+    /// Returns the address to listen on for incoming connections.
+    pub fn get_listen_addr(&self) -> eyre::Result<SocketAddr> {
+        match &self.network.listen {
+            Some(listen) => parse_socket_addr(listen),
+            None => Ok(SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                self.port,
+            )),
+        }
+    }
+
+    /// Returns the address at which the other peers can dial the node.
+    pub fn get_dialable_addr(&self) -> eyre::Result<SocketAddr> {
+        match &self.network.dialable {
+            Some(dialable) => parse_socket_addr(dialable),
+            None => Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), self.port)),
+        }
+    }
+
+    /// Returns the parsed public keys and endpoints of the additional bootstrappers.
+    pub fn get_bootstrappers(&self) -> eyre::Result<Vec<(PublicKey, SocketAddr)>> {
+        self.network
+            .bootstrappers
+            .iter()
+            .map(|peer| Ok((peer.get_public_key()?, peer.get_endpoint()?)))
+            .collect()
+    }
+
+    /// Builds the discovery config of the p2p network, which is bootstrapped by the known peers
+    /// that are allowed by the policy and the additional bootstrappers.
+    pub fn get_p2p_config(&self) -> eyre::Result<discovery::Config<PrivateKey>> {
+        let policy = self.get_policy()?;
+        let bootstrappers = self
+            .get_peers()?
+            .into_iter()
+            .filter(|(public_key, _)| policy.allows(public_key))
+            .chain(self.get_bootstrappers()?)
+            .collect::<Vec<_>>();
+
+        let (listen, dialable) = (self.get_listen_addr()?, self.get_dialable_addr()?);
+        let max_message_size = self.get_channels()?.max_message_size;
+        let signer = self.get_private_key();
+        let p2p_config = match self.network.mode {
+            NetworkMode::Local => discovery::Config::local(
+                signer,
+                NAMESPACE,
+                listen,
+                dialable,
+                bootstrappers,
+                max_message_size,
+            ),
+            NetworkMode::Production => {
+                let mut p2p_config = discovery::Config::recommended(
+                    signer,
+                    NAMESPACE,
+                    listen,
+                    dialable,
+                    bootstrappers,
+                    max_message_size,
+                );
+                p2p_config.allow_private_ips = self.network.allow_private_ips;
+                p2p_config
+            }
+        };
+
+        Ok(p2p_config)
+    }
+
+    /// Validates the network configuration.
+    pub(super) fn validate_network(&self) -> eyre::Result<()> {
+        let _ = self.get_listen_addr()?;
+        let _ = self.get_dialable_addr()?;
+        // NOTE: the default dialable address is localhost, which other hosts can't reach.
+        if self.network.mode == NetworkMode::Production && self.network.dialable.is_none() {
+            return Err(eyre::eyre!(
+                "dialable address must be set in production mode"
+            ));
+        }
+
+        let peers = self.get_peers()?;
+        for (public_key, _) in self.get_bootstrappers()? {
+            if public_key == self.get_public_key() {
+                return Err(eyre::eyre!("own public key can't be a bootstrapper"));
+            }
+            if peers.iter().any(|(peer, _)| *peer == public_key) {
+                return Err(eyre::eyre!(
+                    "known peer can't be an additional bootstrapper: {}",
+                    public_key
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use commonware_cryptography::{PrivateKeyExt as _, Signer};
+    use commonware_macros::select;
+    use commonware_p2p::{Manager, Receiver, Recipients, Sender};
+    use commonware_runtime::{Clock, Metrics, Runner, tokio};
+    use commonware_utils::NZU32;
+    use governor::Quota;
+
+    /// Returns an address on the given host with a port that's currently free.
+    fn free_addr(host: Ipv4Addr) -> SocketAddr {
+        std::net::TcpListener::bind((host, 0))
+            .and_then(|listener| listener.local_addr())
+            .expect("failed to find free port")
+    }
+
+    /// Builds the production config of a player, listening on the given loopback address.
+    fn lan_config(seed: u64, addr: SocketAddr, peer: (u64, SocketAddr)) -> Config {
+        let mut config = Config::new(
+            &PrivateKey::from_seed(seed),
+            addr.port(),
+            vec![Peer::new(
+                &peer.1.to_string(),
+                &PrivateKey::from_seed(peer.0).public_key().to_string(),
+            )],
+        );
+        config.network = NetworkConfig {
+            mode: NetworkMode::Production,
+            listen: Some(addr.to_string()),
+            dialable: Some(addr.to_string()),
+            bootstrappers: Vec::new(),
+            allow_private_ips: true,
+        };
+
+        config
+    }
+
+    #[test]
+    fn test_addresses() {
+        let mut config = Config::new(&PrivateKey::from_seed(0), 5670, Vec::new());
+        assert_eq!(
+            "0.0.0.0:5670",
+            config.get_listen_addr().unwrap().to_string()
+        );
+        assert_eq!(
+            "127.0.0.1:5670",
+            config.get_dialable_addr().unwrap().to_string()
+        );
+
+        config.network.listen = Some("192.168.1.2:5680".into());
+        config.network.dialable = Some("192.168.1.2:5680".into());
+        assert_eq!(
+            "192.168.1.2:5680",
+            config.get_listen_addr().unwrap().to_string()
+        );
+        assert_eq!(
+            "192.168.1.2:5680",
+            config.get_dialable_addr().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_network_config() {
+        let peer = PrivateKey::from_seed(1).public_key().to_string();
+        let mut config = Config::new(
+            &PrivateKey::from_seed(0),
+            5670,
+            vec![Peer::new("127.0.0.1:5671", &peer)],
+        );
+        assert!(config.validate().is_ok());
+
+        // the other hosts can't dial the node at the default address
+        config.network.mode = NetworkMode::Production;
+        assert!(config.validate().is_err());
+        config.network.dialable = Some("192.168.1.2:5670".into());
+        assert!(config.validate().is_ok());
+
+        config.network.listen = Some("abc".into());
+        assert!(config.validate().is_err());
+        config.network.listen = None;
+
+        // the known peers are already used as bootstrappers
+        config.network.bootstrappers = vec![Peer::new("192.168.1.3:5670", &peer)];
+        assert!(config.validate().is_err());
+        config.network.bootstrappers = vec![Peer::new(
+            "192.168.1.3:5670",
+            &PrivateKey::from_seed(2).public_key().to_string(),
+        )];
+        assert!(config.validate().is_ok());
+        assert_eq!(2, config.get_p2p_config().unwrap().bootstrappers.len());
+    }
+
+    #[test]
+    #[ignore = "requires the loopback addresses 127.0.0.2 and 127.0.0.3, which e.g. macOS lacks"]
+    fn test_separate_hosts() {
+        // NOTE: the loopback addresses stand in for two hosts on the same LAN.
+        let hosts = [
+            free_addr(Ipv4Addr::new(127, 0, 0, 2)),
+            free_addr(Ipv4Addr::new(127, 0, 0, 3)),
+        ];
+        let configs = [
+            lan_config(0, hosts[0], (1, hosts[1])),
+            lan_config(1, hosts[1], (0, hosts[0])),
+        ];
+        for config in &configs {
+            config.validate().expect("invalid config");
+        }
+
+        let executor = tokio::Runner::new(tokio::Config::new());
+        let received = executor.start(|context| async move {
+            let mut channels = Vec::new();
+            for (id, config) in configs.iter().enumerate() {
+                let (mut network, mut oracle) = discovery::Network::new(
+                    context.with_label(&format!("network_{}", id)),
+                    config.get_p2p_config().expect("invalid p2p config"),
+                );
+                let peers = config
+                    .get_peers()
+                    .unwrap()
+                    .into_iter()
+                    .map(|(public_key, _)| public_key)
+                    .collect::<Vec<_>>();
+                oracle.update(0, peers.into()).await;
+
+                channels.push(network.register(0, Quota::per_second(NZU32!(10)), 16));
+                network.start();
+            }
+            let (mut sender, _) = channels.remove(0);
+            let (_, mut receiver) = channels.remove(0);
+
+            // The message is resent until the connection between both hosts is established.
+            let deadline = context.current() + Duration::from_secs(30);
+            loop {
+                let _ = sender
+                    .send(Recipients::All, b"hello".as_slice().into(), false)
+                    .await;
+
+                select! {
+                    msg = receiver.recv() => {
+                        let (peer, message) = msg.expect("failed to receive message");
+                        return Some((peer, message));
+                    },
+                    _ = context.sleep(Duration::from_millis(500)) => {
+                        if context.current() > deadline {
+                            return None;
+                        }
+                    },
+                }
+            }
+        });
+
+        let (peer, message) = received.expect("hosts did not connect");
+        assert_eq!(PrivateKey::from_seed(0).public_key(), peer);
+        assert_eq!(b"hello".as_slice(), message.as_ref());
+    }
+}
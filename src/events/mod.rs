@@ -0,0 +1,189 @@
+//! Events published by the game state actor and the lobby.
+//!
+//! The actors do not drive a specific frontend, but publish their events
+//! to an [`EventSink`]. Every subscriber (e.g. the TUI or the NDJSON writer of the headless mode)
+//! receives all events and decides on its own which of them to handle.
+mod ndjson;
+
+pub use ndjson::NdjsonActor;
+
+use futures::{SinkExt, channel::mpsc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{Move, Summary},
+    gui::{GridView, Log},
+    lobby::PeerStatus,
+};
+
+/// The player an event refers to, from the perspective of the publishing actor.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Own,
+    Opponent,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A ready message was sent by the given player.
+    Handshake { from: Side },
+    /// Both players are ready and the given player has the first turn.
+    Start { first: Side },
+    /// An attack was sent by the given player.
+    Attack {
+        by: Side,
+        number: u16,
+        x: u8,
+        y: u8,
+        position: String,
+    },
+    /// The result of an attack of the given player.
+    Result {
+        by: Side,
+        number: u16,
+        x: u8,
+        y: u8,
+        position: String,
+        hit: bool,
+    },
+    /// A ship was sunk by the given player.
+    ///
+    /// NOTE: the protocol does not reveal if an attack sunk a ship of the opponent,
+    /// so this is only published for the player's own ships.
+    Sunk { by: Side, ship: String },
+    /// The game ended with the given winner, or without one in case of an error.
+    End { winner: Option<Side> },
+    /// An error that ended the game.
+    Error { message: String },
+    /// Both players revealed their ships, so the summary of the game is available.
+    GameOver { summary: Summary },
+    /// A chat message was sent by the given player.
+    ///
+    /// NOTE: the chat is private to the players, so it's never shown to the spectators.
+    Chat { by: Side, text: String },
+    /// The status of the known peers in the lobby changed.
+    Lobby { peers: Vec<PeerStatus> },
+    /// The player's own grid and the view of the opponent's grid were updated.
+    #[serde(skip)]
+    Grids { own: GridView, opponent: GridView },
+    /// A human-readable log.
+    #[serde(skip)]
+    Log { log: Log },
+}
+
+impl Event {
+    /// Creates the attack event for the given move.
+    pub fn attack(by: Side, m: &Move) -> Self {
+        Self::Attack {
+            by,
+            number: m.get_number(),
+            x: m.get_x(),
+            y: m.get_y(),
+            position: m.get_position(),
+        }
+    }
+
+    /// Creates the result event for the given move.
+    pub fn result(by: Side, m: &Move) -> Self {
+        Self::Result {
+            by,
+            number: m.get_number(),
+            x: m.get_x(),
+            y: m.get_y(),
+            position: m.get_position(),
+            hit: m.is_hit,
+        }
+    }
+
+    /// Checks if the event is only relevant for rendering the game, i.e. not a game event.
+    pub fn is_rendering(&self) -> bool {
+        matches!(self, Self::Grids { .. } | Self::Log { .. })
+    }
+}
+
+/// Publishes the events to all subscribers.
+#[derive(Clone, Default)]
+pub struct EventSink {
+    subscribers: Vec<mpsc::Sender<Event>>,
+}
+
+impl EventSink {
+    pub fn new(subscribers: Vec<mpsc::Sender<Event>>) -> Self {
+        Self { subscribers }
+    }
+
+    /// Adds a subscriber that receives all events published afterwards.
+    pub fn subscribe(&mut self, subscriber: mpsc::Sender<Event>) {
+        self.subscribers.push(subscriber);
+    }
+
+    /// Publishes the given event to all subscribers.
+    ///
+    /// NOTE: publishing is best-effort, so the subscribers that stopped (e.g. the GUI after
+    /// quitting) are dropped, while the remaining subscribers still receive the event.
+    pub async fn publish(&mut self, event: Event) {
+        let mut subscribers = Vec::with_capacity(self.subscribers.len());
+        for mut subscriber in std::mem::take(&mut self.subscribers) {
+            if subscriber.send(event.clone()).await.is_ok() {
+                subscribers.push(subscriber);
+            }
+        }
+
+        self.subscribers = subscribers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::{StreamExt, executor::block_on};
+
+    #[test]
+    fn test_serialize() {
+        let m = Move::new(3, 2, 1, true);
+        assert_eq!(
+            r#"{"event":"result","by":"opponent","number":3,"x":2,"y":1,"position":"B1","hit":true}"#,
+            serde_json::to_string(&Event::result(Side::Opponent, &m)).unwrap()
+        );
+        assert_eq!(
+            r#"{"event":"end","winner":null}"#,
+            serde_json::to_string(&Event::End { winner: None }).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_publish() {
+        let (first, mut first_events) = mpsc::channel(1);
+        let (second, mut second_events) = mpsc::channel(1);
+        let mut sink = EventSink::new(vec![first]);
+        sink.subscribe(second);
+
+        block_on(sink.publish(Event::Start { first: Side::Own }));
+
+        for events in [&mut first_events, &mut second_events] {
+            assert!(matches!(
+                block_on(events.next()),
+                Some(Event::Start { first: Side::Own })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stopped_subscriber() {
+        let (first, first_events) = mpsc::channel(1);
+        let (second, mut second_events) = mpsc::channel(1);
+        let mut sink = EventSink::new(vec![first, second]);
+
+        // the stopped subscriber is dropped, while the other one still receives the event
+        drop(first_events);
+        block_on(sink.publish(Event::Start { first: Side::Own }));
+        assert_eq!(1, sink.subscribers.len());
+        assert!(matches!(
+            block_on(second_events.next()),
+            Some(Event::Start { first: Side::Own })
+        ));
+    }
+}
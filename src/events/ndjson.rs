@@ -0,0 +1,106 @@
+use std::{io::Write, time::Duration};
+
+use commonware_macros::select;
+use commonware_runtime::{Clock, ContextCell, Spawner, spawn_cell};
+use futures::{
+    StreamExt,
+    channel::mpsc::{self, Receiver},
+    future,
+};
+
+use super::Event;
+
+/// The duration to wait for the summary once the game ended with a winner.
+///
+/// NOTE: the summary is only published once the opponent revealed its ships,
+/// which e.g. a disconnected opponent never does.
+const REVEAL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Writes the game events as newline-delimited JSON, e.g. for bots or CI runs.
+///
+/// The events that are only relevant for rendering (grids and logs) are not written.
+pub struct NdjsonActor<R: Spawner + Clock> {
+    context: ContextCell<R>,
+    mailbox: Receiver<Event>,
+    writer: Box<dyn Write + Send>,
+
+    /// Signals if the runtime is stopped once the game ended.
+    ///
+    /// NOTE: in headless mode there is no TUI to quit, so this is used to exit the process.
+    stop_on_end: bool,
+}
+
+impl<R: Spawner + Clock> NdjsonActor<R> {
+    pub fn new(
+        context: R,
+        writer: Box<dyn Write + Send>,
+        stop_on_end: bool,
+    ) -> (Self, mpsc::Sender<Event>) {
+        // TODO: use other size here?
+        let (tx, rx) = mpsc::channel(16);
+
+        (
+            Self {
+                context: ContextCell::new(context),
+                mailbox: rx,
+                writer,
+                stop_on_end,
+            },
+            tx,
+        )
+    }
+
+    pub fn start(mut self) {
+        spawn_cell!(self.context, self.run().await);
+    }
+
+    async fn run(mut self) {
+        // NOTE: the clock is cloned, so that the timeout does not borrow the actor in the loop.
+        let clock = self.context.as_present().clone();
+        // The time at which the runtime is stopped, if the summary was not published until then.
+        let mut deadline = None;
+
+        loop {
+            let timeout = deadline.map(|deadline| clock.sleep_until(deadline));
+            let reveal = async move {
+                match timeout {
+                    Some(timeout) => timeout.await,
+                    None => future::pending().await,
+                }
+            };
+
+            select! {
+                event = self.mailbox.next() => {
+                    let Some(event) = event else {
+                        return;
+                    };
+                    if event.is_rendering() {
+                        continue;
+                    }
+
+                    let line = serde_json::to_string(&event).expect("failed to serialize event");
+                    writeln!(self.writer, "{}", line).expect("failed to write event");
+                    self.writer.flush().expect("failed to flush events");
+
+                    if !self.stop_on_end {
+                        continue;
+                    }
+                    match event {
+                        // The game is over once the summary is published, or if it ended without a winner
+                        // (e.g. due to an error), in which case there won't be a summary.
+                        Event::GameOver { .. } | Event::End { winner: None } => break,
+                        Event::End { winner: Some(_) } => {
+                            deadline = Some(clock.current() + REVEAL_TIMEOUT);
+                        }
+                        _ => (),
+                    }
+                },
+                _ = reveal => {
+                    break;
+                },
+            }
+        }
+
+        let _ = clock.stop(0, None).await;
+    }
+}
@@ -3,9 +3,11 @@
 //! NOTE: This implementation is adapted based on  https://github.com/orhun/battleship-rs.
 
 use super::ship::{Ship, ShipType};
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
 use std::io::{Result as IoResult, Write};
+use std::ops::RangeInclusive;
 use std::result::Result as StdResult;
 use std::str;
 
@@ -19,6 +21,10 @@ const MISSED_POINT: &str = "✕";
 const DEFAULT_POINT: &str = "•";
 /// Defines the grid size for the game.
 pub const GRID_SIZE: u8 = 5;
+/// The number of ships on a random grid, besides the single [`Battleship`].
+///
+/// [`Battleship`]: ShipType::Battleship
+pub const SHIP_COUNT: RangeInclusive<usize> = 4..=7;
 
 /// Representation of coordinates on a 2-dimensional plane.
 #[derive(Clone, Copy, Default)]
@@ -97,6 +103,33 @@ impl From<(u8, u8)> for Coordinate {
     }
 }
 
+/// The state of a single point on the grid, as it's shown to the player.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Cell {
+    /// A point without a ship, or a point that was not attacked yet in the opponent view.
+    Empty,
+    /// A part of a ship that was not hit yet.
+    Ship(ShipType),
+    /// A point that was attacked and hit a ship.
+    Hit,
+    /// A point that was attacked without hitting a ship.
+    Miss,
+    /// A part of a ship that was completely sunk.
+    Sunk,
+}
+
+impl Cell {
+    /// Returns the character representing the cell.
+    pub fn symbol(&self) -> String {
+        match self {
+            Cell::Empty => DEFAULT_POINT.to_string(),
+            Cell::Ship(ship_type) => ship_type.to_string(),
+            Cell::Hit | Cell::Sunk => HIT_POINT.to_string(),
+            Cell::Miss => MISSED_POINT.to_string(),
+        }
+    }
+}
+
 /// Representation of the game grid.
 #[derive(Default, Debug)]
 pub struct Grid {
@@ -126,7 +159,7 @@ impl Grid {
     /// [`Battleship`]: ShipType::Battleship
     pub fn new_random(width: u8, height: u8) -> Self {
         let mut grid = Grid::new(width, height);
-        let ship_count = fastrand::usize(4..=7);
+        let ship_count = fastrand::usize(SHIP_COUNT);
         let mut battleship = None;
         while grid.ships.len() != ship_count {
             let ship = Ship::new_random(grid.width, grid.height);
@@ -164,6 +197,25 @@ impl Grid {
         Ok(())
     }
 
+    /// Checks if the ships form a fleet as placed by [`Self::new_random`], i.e. every ship has
+    /// the shape of its type and there's at most one [`Battleship`] besides the [`SHIP_COUNT`] ships.
+    ///
+    /// [`Battleship`]: ShipType::Battleship
+    pub fn is_standard_fleet(&self) -> bool {
+        let shaped = self.ships.iter().all(|ship| {
+            ship.coords
+                .first()
+                .is_some_and(|origin| ship.type_.get_hitbox(*origin) == ship.coords)
+        });
+        let battleships = self
+            .ships
+            .iter()
+            .filter(|ship| matches!(ship.type_, ShipType::Battleship(_)))
+            .count();
+
+        shaped && battleships <= 1 && SHIP_COUNT.contains(&(self.ships.len() - battleships))
+    }
+
     /// Places a ship on the grid.
     ///
     /// Returns `false` if the ship is overlapping with other ships
@@ -195,6 +247,46 @@ impl Grid {
         Ok(str::from_utf8(&s)?.to_string())
     }
 
+    /// Returns the state of the given point on the grid.
+    ///
+    /// Ships (and whether they are sunk) are only shown if `show_ships` is true.
+    /// Otherwise, all points that are part of the ships are considered to be attacks,
+    /// as described in [`Self::mark_hit`].
+    pub fn cell(&self, coordinate: Coordinate, show_ships: bool) -> Cell {
+        let Some(ship) = self
+            .ships
+            .iter()
+            .find(|ship| ship.coords.contains(&coordinate))
+        else {
+            return Cell::Empty;
+        };
+
+        let is_hit = ship
+            .coords
+            .iter()
+            .find(|c| *c == &coordinate)
+            .map(|c| c.is_hit)
+            == Some(true);
+
+        match (is_hit, show_ships) {
+            (true, true) if ship.is_sunk() => Cell::Sunk,
+            (true, _) => Cell::Hit,
+            (false, true) => Cell::Ship(ship.type_),
+            (false, false) => Cell::Miss,
+        }
+    }
+
+    /// Returns the states of all points on the grid, row by row.
+    pub fn cells(&self, show_ships: bool) -> Vec<Vec<Cell>> {
+        (1..=self.height)
+            .map(|y| {
+                (1..=self.width)
+                    .map(|x| self.cell(Coordinate::from((x, y)), show_ships))
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Display a point on the grid.
     ///
     /// The point might be empty or a part of a ship.
@@ -204,32 +296,7 @@ impl Grid {
         coordinate: Coordinate,
         show_ships: bool,
     ) -> IoResult<()> {
-        if let Some(ship) = self
-            .ships
-            .iter()
-            .find(|ship| ship.coords.contains(&coordinate))
-        {
-            write!(
-                out,
-                "{} ",
-                if ship
-                    .coords
-                    .iter()
-                    .find(|c| *c == &coordinate)
-                    .map(|c| c.is_hit)
-                    == Some(true)
-                {
-                    HIT_POINT.to_string()
-                } else if show_ships {
-                    ship.type_.to_string()
-                } else {
-                    MISSED_POINT.to_string()
-                }
-            )?;
-        } else {
-            write!(out, "{} ", DEFAULT_POINT)?;
-        }
-        Ok(())
+        write!(out, "{} ", self.cell(coordinate, show_ships).symbol())
     }
 
     /// Prints the grid to the given output.
@@ -334,4 +401,71 @@ mod tests {
         let grid = Grid::new_random(15, 15);
         assert!(!grid.ships.is_empty());
     }
+
+    #[test]
+    fn test_standard_fleet() {
+        for _ in 0..10 {
+            assert!(Grid::new_random(GRID_SIZE, GRID_SIZE).is_standard_fleet());
+        }
+
+        let boats = |count: u8| {
+            let mut grid = Grid::new(GRID_SIZE, GRID_SIZE);
+            for x in 1..=count {
+                assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((x, 1))])));
+            }
+            grid
+        };
+        assert!(boats(4).is_standard_fleet());
+        assert!(!boats(0).is_standard_fleet());
+        assert!(!boats(3).is_standard_fleet());
+
+        // every ship must have the shape of its type
+        let mut grid = boats(4);
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Destroyer(Orientation::Vertical),
+            vec![Coordinate::from((1, 3))]
+        )));
+        assert!(!grid.is_standard_fleet());
+
+        // only a single battleship is allowed besides the other ships
+        let battleship = |x| {
+            let type_ = ShipType::Battleship(Orientation::Horizontal);
+            Ship::new(type_, type_.get_hitbox(Coordinate::from((x, 2))))
+        };
+        let mut grid = boats(4);
+        assert!(grid.place_ship(battleship(1)));
+        assert!(grid.is_standard_fleet());
+        let mut grid = boats(3);
+        assert!(grid.place_ship(battleship(1)));
+        assert!(!grid.is_standard_fleet());
+    }
+
+    #[test]
+    fn test_cells() {
+        let mut grid = Grid::new(3, 2);
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Destroyer(Orientation::Horizontal),
+            vec![Coordinate::new(1, 1, true), Coordinate::new(2, 1, false)]
+        )));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Boat,
+            vec![Coordinate::new(3, 2, true)]
+        )));
+
+        let destroyer = Cell::Ship(ShipType::Destroyer(Orientation::Horizontal));
+        assert_eq!(
+            vec![
+                vec![Cell::Hit, destroyer, Cell::Empty],
+                vec![Cell::Empty, Cell::Empty, Cell::Sunk],
+            ],
+            grid.cells(true)
+        );
+        assert_eq!(
+            vec![
+                vec![Cell::Hit, Cell::Miss, Cell::Empty],
+                vec![Cell::Empty, Cell::Empty, Cell::Hit],
+            ],
+            grid.cells(false)
+        );
+    }
 }
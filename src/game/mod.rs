@@ -2,5 +2,6 @@ mod grid;
 mod player;
 mod ship;
 
-pub use grid::{Coordinate, GRID_SIZE};
+pub use grid::{Cell, Coordinate, GRID_SIZE};
 pub use player::Player;
+pub use ship::{Orientation, Ship, ShipType};
@@ -4,7 +4,8 @@
 //! It has been adapted to make use of the Commonware components.
 
 use super::grid::Coordinate;
-use super::grid::{GRID_SIZE, Grid};
+use super::grid::{Cell, GRID_SIZE, Grid};
+use super::ship::{Ship, ShipType};
 
 /// Representation of a player.
 #[derive(Debug)]
@@ -13,6 +14,8 @@ pub struct Player {
     pub grid: Grid,
     /// The opponent's grid, as viewed by the attacker (i.e. empty except for hits or misses marked).
     pub opponent_grid: Grid,
+    /// The opponent's attacks that missed the player's ships.
+    pub misses: Vec<Coordinate>,
 }
 
 impl Default for Player {
@@ -27,9 +30,32 @@ impl Player {
         Self {
             grid: Grid::new_random(GRID_SIZE, GRID_SIZE),
             opponent_grid: Grid::new(GRID_SIZE, GRID_SIZE),
+            misses: Vec::new(),
         }
     }
 
+    /// Constructs a player with the given ships, e.g. to reconstruct the opponent's board
+    /// from the ships revealed at the end of the game.
+    ///
+    /// The ships must form a standard fleet (see [`Grid::is_standard_fleet`]).
+    pub fn from_ships(ships: Vec<Ship>) -> eyre::Result<Self> {
+        let mut grid = Grid::new(GRID_SIZE, GRID_SIZE);
+        for ship in ships {
+            if ship.coords.iter().any(|c| c.x == 0 || c.y == 0) || !grid.place_ship(ship) {
+                return Err(eyre::eyre!("invalid ship placement"));
+            }
+        }
+        if !grid.is_standard_fleet() {
+            return Err(eyre::eyre!("ships do not form a standard fleet"));
+        }
+
+        Ok(Self {
+            grid,
+            opponent_grid: Grid::new(GRID_SIZE, GRID_SIZE),
+            misses: Vec::new(),
+        })
+    }
+
     /// Sets a given coordinate (which MUST include the hit or miss information)
     /// in the locally stored opponent grid.
     pub fn attack(&mut self, x: u8, y: u8, is_hit: bool) -> eyre::Result<()> {
@@ -52,9 +78,39 @@ impl Player {
             return true;
         }
 
+        self.misses.push(expected);
         false
     }
 
+    /// Returns the type of the ship at the given coordinate, if it's sunk.
+    pub fn sunk_ship(&self, x: u8, y: u8) -> Option<ShipType> {
+        let expected = Coordinate::new(x, y, false);
+
+        self.grid
+            .ships
+            .iter()
+            .find(|ship| ship.coords.contains(&expected))
+            .filter(|ship| ship.is_sunk())
+            .map(|ship| ship.type_)
+    }
+
+    /// Returns the player's own view of the grid, including the ships
+    /// as well as the hits and misses of the opponent.
+    pub fn own_cells(&self) -> Vec<Vec<Cell>> {
+        let mut cells = self.grid.cells(true);
+
+        for miss in &self.misses {
+            if let Some(cell) = cells
+                .get_mut((miss.y as usize).wrapping_sub(1))
+                .and_then(|row| row.get_mut((miss.x as usize).wrapping_sub(1)))
+            {
+                *cell = Cell::Miss;
+            }
+        }
+
+        cells
+    }
+
     /// Checks if the player has lost the game.
     ///
     /// This is the case if all coordinates of all placed ships have
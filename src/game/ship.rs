@@ -3,6 +3,7 @@
 //! NOTE: This implementation is copied from https://github.com/orhun/battleship-rs.
 
 use super::grid::Coordinate;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// The character that represents a boat.
@@ -15,7 +16,7 @@ const DESTROYER_HORIZONTAL: &str = "▭";
 const BATTLESHIP: &str = "▧";
 
 /// Available orientations for the ship.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Orientation {
     /// Vertical placement.
     Vertical,
@@ -24,7 +25,7 @@ pub enum Orientation {
 }
 
 /// Available ship types.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum ShipType {
     /// 1x1 boat.
     Boat,
@@ -77,6 +78,15 @@ impl ShipType {
         ]
     }
 
+    /// Returns the name of the ship type, independent of its orientation.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Boat => "boat",
+            Self::Destroyer(_) => "destroyer",
+            Self::Battleship(_) => "battleship",
+        }
+    }
+
     /// Returns a random [`ShipType`].
     pub fn new_random() -> Self {
         let types = Self::variants();
@@ -1,131 +1,303 @@
-use std::io;
+use std::{io, panic, time::Duration};
 
+use commonware_macros::select;
 use commonware_runtime::{ContextCell, Metrics, Spawner, spawn_cell};
 use futures::{
-    StreamExt,
-    channel::mpsc::{self, Receiver},
+    SinkExt, StreamExt,
+    channel::mpsc::{self, Receiver, TrySendError},
+    executor::block_on,
+    stream::{self, BoxStream},
 };
 use rand::Rng;
 use ratatui::{
-    Frame, Terminal,
+    Terminal,
     backend::CrosstermBackend,
     crossterm::{
+        cursor::Show,
+        event::{self, KeyEvent, KeyEventKind},
         execute,
-        terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
     },
-    layout::{Constraint, Layout, Rect},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
-use super::ingress::{Log, Mailbox, Message};
+use crate::{application::Mailbox, events::Event, lobby};
+
+use super::{
+    ingress::{self, Board, Log, LogType},
+    theme::Theme,
+    view::{Action, View},
+};
+
+/// The maximum duration the other actors may take to shut down after quitting.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct GuiActor<R: Rng + Spawner + Metrics> {
     context: ContextCell<R>,
-    mailbox: Receiver<Message>,
+    /// The events to render, one mailbox per board.
+    mailboxes: Vec<Receiver<Event>>,
+
+    /// The boards that are added while running, which are shown as tabs.
+    ///
+    /// NOTE: this is only set for the tabbed mode and taken out of the actor once it's running.
+    boards: Option<Receiver<Board>>,
+
+    /// The styles to render the grids with.
+    theme: Theme,
 }
 
 impl<R: Rng + Spawner + Metrics> GuiActor<R> {
-    pub fn new(context: R) -> (Self, Mailbox) {
+    pub fn new(context: R, theme: Theme) -> (Self, mpsc::Sender<Event>) {
+        let (actor, mut mailboxes) = Self::with_boards(context, 1, theme);
+
+        (actor, mailboxes.remove(0))
+    }
+
+    /// Creates a GUI actor rendering the given number of boards side by side.
+    ///
+    /// Returns one mailbox per board, which is subscribed to the events of the corresponding player.
+    pub fn with_boards(
+        context: R,
+        boards: usize,
+        theme: Theme,
+    ) -> (Self, Vec<mpsc::Sender<Event>>) {
+        assert!(boards > 0, "must render at least one board");
+
         // TODO: use other size here?
-        let (tx, rx) = mpsc::channel(1);
+        let (senders, mailboxes): (Vec<_>, Vec<_>) =
+            (0..boards).map(|_| mpsc::channel(1)).unzip();
+
+        (
+            Self {
+                context: ContextCell::new(context),
+                mailboxes,
+                boards: None,
+                theme,
+            },
+            senders,
+        )
+    }
+
+    /// Creates a GUI actor showing one board at a time, with a tab for every board.
+    ///
+    /// The boards are added through the returned mailbox, e.g. once a new game was started.
+    pub fn with_tabs(context: R, theme: Theme) -> (Self, ingress::Mailbox) {
+        let (sender, receiver) = mpsc::channel(1);
 
         (
             Self {
                 context: ContextCell::new(context),
-                mailbox: rx,
+                mailboxes: Vec::new(),
+                boards: Some(receiver),
+                theme,
             },
-            Mailbox::new(tx),
+            ingress::Mailbox::new(sender),
         )
     }
 
-    pub fn start(mut self) {
-        spawn_cell!(self.context, self.run().await);
+    /// Starts the GUI actor, sending the commands of the user (e.g. to save a transcript)
+    /// to the game state actor of the corresponding board.
+    ///
+    /// The commands in the lobby (e.g. to challenge a peer) are sent to the lobby actor, if any.
+    pub fn start(mut self, mailboxes: Vec<Mailbox>, lobby: Option<lobby::Mailbox>) {
+        spawn_cell!(self.context, self.run(mailboxes, lobby).await);
     }
 
-    async fn run(mut self) {
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen).expect("failed to execute gui macro");
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend).expect("failed to create terminal gui");
+    async fn run(mut self, mailboxes: Vec<Mailbox>, mut lobby: Option<lobby::Mailbox>) {
+        // The terminal is restored when the guard is dropped or if any task panics,
+        // so that the shell is not left in raw mode.
+        let guard = TerminalGuard::enter();
+        let mut terminal =
+            Terminal::new(CrosstermBackend::new(io::stdout())).expect("failed to create terminal gui");
+
+        let mut view = match self.boards {
+            Some(_) => View::tabbed(self.theme.clone()),
+            None => View::new(self.mailboxes.len(), self.theme.clone()),
+        };
+
+        // The mailboxes of the game state actors, in the order of the boards.
+        let mut mailboxes = mailboxes.into_iter().map(Some).collect::<Vec<_>>();
+
+        // The events of all boards are merged into a single stream, tagged with their board.
+        let mut events = stream::select_all(
+            std::mem::take(&mut self.mailboxes)
+                .into_iter()
+                .enumerate()
+                .map(|(board, mailbox)| board_events(board, mailbox)),
+        );
+
+        // NOTE: in the tabbed mode, the boards are added while running, so the events must not
+        // end once all current boards stopped. The boards are chained with a pending stream,
+        // so that the loop is not woken up repeatedly if there is no mailbox to add boards.
+        let mut boards = match self.boards.take() {
+            Some(boards) => {
+                events.push(stream::pending().boxed());
+                boards.boxed()
+            }
+            None => stream::empty().boxed(),
+        }
+        .chain(stream::pending());
 
         // Before receiving any messages we will draw an empty frame.
         terminal
-            .draw(|frame| self.draw_empty(frame))
+            .draw(|frame| view.draw(frame))
             .expect("failed to draw");
 
-        let mut grid_string: String = "".into();
-        let mut logs: Vec<Log> = vec![];
+        // Crossterm only offers a blocking API to read the terminal events,
+        // so the key presses are read on a blocking task and forwarded to the actor's loop.
+        let (keys_sender, mut keys) = mpsc::channel(16);
+        self.context
+            .as_present()
+            .clone()
+            .spawn_blocking(true, move |_| read_keys(keys_sender));
 
-        while let Some(message) = self.mailbox.next().await {
-            terminal
-                .draw(|frame| {
-                    let [left, right] = create_layout(frame);
+        let mut quit = false;
+        loop {
+            select! {
+                event = events.next() => {
+                    let Some((board, event)) = event else {
+                        break;
+                    };
+                    view.handle_event(board, event);
+                },
+                board = boards.next() => {
+                    let Some(board) = board else {
+                        continue;
+                    };
 
-                    match message {
-                        Message::Draw { grid: g } => {
-                            grid_string = g.to_owned();
-                        }
-                        Message::Log { log } => {
-                            logs.push(log);
-                        }
+                    let index = view.add_board(board.title);
+                    events.push(board_events(index, board.events));
+                    mailboxes.push(board.mailbox);
+                },
+                key = keys.next() => {
+                    let Some(key) = key else {
+                        break;
                     };
 
-                    let grid = self.draw_grid(&grid_string);
-                    frame.render_widget(grid, left);
+                    // NOTE: the commands are only best effort, since the game state actor
+                    // might have already stopped or be busy.
+                    match view.handle_key(key) {
+                        Action::Quit => {
+                            quit = true;
+                            break;
+                        }
+                        Action::SaveTranscript(board) => {
+                            if let Some(Some(mailbox)) = mailboxes.get_mut(board) {
+                                notify_busy(&mut view, board, mailbox.save_transcript());
+                            }
+                        }
+                        Action::Rematch(board) => {
+                            if let Some(Some(mailbox)) = mailboxes.get_mut(board) {
+                                notify_busy(&mut view, board, mailbox.rematch());
+                            }
+                        }
+                        Action::Chat(board, text) => {
+                            if let Some(Some(mailbox)) = mailboxes.get_mut(board) {
+                                notify_busy(&mut view, board, mailbox.chat(text));
+                            }
+                        }
+                        Action::Challenge(peer) => {
+                            if let Some(lobby) = lobby.as_mut() {
+                                let _ = lobby.challenge(peer);
+                            }
+                        }
+                        Action::Accept(peer) => {
+                            if let Some(lobby) = lobby.as_mut() {
+                                let _ = lobby.accept(peer);
+                            }
+                        }
+                        Action::Decline(peer) => {
+                            if let Some(lobby) = lobby.as_mut() {
+                                let _ = lobby.decline(peer);
+                            }
+                        }
+                        Action::None => (),
+                    }
+                },
+                _ = self.context.as_present().stopped() => {
+                    break;
+                },
+            }
 
-                    let list = self.put_logs(&logs);
-                    frame.render_widget(list, right);
-                })
+            terminal
+                .draw(|frame| view.draw(frame))
                 .expect("failed to draw");
         }
 
-        execute!(terminal.backend_mut(), LeaveAlternateScreen)
-            .expect("failed to leave alternate screen");
-        terminal.show_cursor().expect("failed to show cursor");
-    }
+        drop(guard);
 
-    pub fn draw_empty(&self, frame: &mut Frame) {
-        let [left, right] = create_layout(frame);
+        // Quitting stops all actors through the runtime, which then shuts down the process.
+        if quit {
+            let _ = self
+                .context
+                .as_present()
+                .clone()
+                .stop(0, Some(SHUTDOWN_TIMEOUT))
+                .await;
+        }
+    }
+}
 
-        let empty_grid = self.draw_grid("");
-        frame.render_widget(empty_grid, left);
+/// Tags the events of the given board's mailbox with the index of the board.
+fn board_events(board: usize, mailbox: Receiver<Event>) -> BoxStream<'static, (usize, Event)> {
+    mailbox.map(move |event| (board, event)).boxed()
+}
 
-        let empty = Vec::new();
-        let empty_logs = self.put_logs(&empty);
-        frame.render_widget(empty_logs, right);
+/// Shows a log on the given board if the game state actor was too busy to accept a command.
+fn notify_busy<T>(view: &mut View, board: usize, result: Result<(), TrySendError<T>>) {
+    if result.is_err_and(|e| e.is_full()) {
+        let log = Log::new(LogType::Info, "game is busy; please try again".into());
+        view.handle_event(board, Event::Log { log });
     }
+}
 
-    // TODO: this could maybe print the own and opponent grids in two windows with
-    // the corresponding log section for own / opponent moves?
-    //
-    // |------------------------|
-    // |.|-------|...|--------|.|
-    // |.|..Grid.|...|..Logs..|.|
-    // |.|-------|...|--------|.|
-    // |.|..Opps.|...|..Logs..|.|
-    // |.|-------|...|--------|.|
-    // |-|--------------------|-|
-    // |-|...General Logs.....|-|
-    // |-|--------------------|-|
-    // |------------------------|
-    //
-    pub fn draw_grid<'a>(&self, grid: &'a str) -> Paragraph<'a> {
-        let block = Block::default().title("Game State").borders(Borders::ALL);
-
-        Paragraph::new(grid).block(block)
-    }
+/// Enables the raw mode and the alternate screen while it's alive.
+///
+/// NOTE: restoring the terminal is idempotent, so it's fine that both the panic hook
+/// and the drop of the guard restore it.
+struct TerminalGuard;
 
-    pub fn put_logs<'a>(&self, logs: &'a [Log]) -> List<'a> {
-        let block = Block::default().title("Logs").borders(Borders::ALL);
+impl TerminalGuard {
+    fn enter() -> Self {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            hook(info);
+        }));
+
+        // The raw mode is required to receive the key presses without waiting for a newline.
+        enable_raw_mode().expect("failed to enable raw mode");
+        execute!(io::stdout(), EnterAlternateScreen).expect("failed to enter alternate screen");
+
+        Self
+    }
+}
 
-        let items = logs.iter().rev().map(|log| ListItem::new(log.to_owned()));
-        List::new(items).block(block)
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
     }
 }
 
-fn create_layout(frame: &mut Frame) -> [Rect; 2] {
-    Layout::default()
-        .direction(ratatui::layout::Direction::Horizontal)
-        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
-        .areas::<2>(frame.area())
+/// Restores the terminal to its state before the TUI was started.
+///
+/// The errors are ignored, since this is also called while panicking.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+}
+
+/// Reads the key presses from the terminal and sends them through the given channel,
+/// until the receiver is dropped.
+fn read_keys(mut sender: mpsc::Sender<KeyEvent>) {
+    loop {
+        let key = match event::read() {
+            Ok(event::Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(_) => return,
+        };
+
+        if block_on(sender.send(key)).is_err() {
+            return;
+        }
+    }
 }
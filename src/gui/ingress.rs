@@ -1,26 +1,66 @@
-use futures::channel::mpsc::Sender;
+use crate::{application, events::Event, game::Cell};
+
+use futures::{
+    SinkExt,
+    channel::mpsc::{Receiver, SendError, Sender},
+};
 use ratatui::{
     style::{Color, Style},
     text::Text,
 };
 
-/// TODO: check if lifetime would better be removed?
+/// A board that's added to the GUI while it's running, e.g. once a new game was started.
+pub struct Board {
+    /// The title of the board's tab.
+    pub title: String,
+    /// The events to render on the board.
+    pub events: Receiver<Event>,
+    /// The mailbox of the game state actor to send the user's commands to, if any.
+    pub mailbox: Option<application::Mailbox>,
+}
+
+/// The mailbox to add boards to the GUI actor.
+#[derive(Clone)]
 pub struct Mailbox {
-    pub sender: Sender<Message>,
+    sender: Sender<Board>,
 }
 
 impl Mailbox {
-    pub fn new(sender: Sender<Message>) -> Self {
+    pub fn new(sender: Sender<Board>) -> Self {
         Self { sender }
     }
+
+    pub async fn add_board(&mut self, board: Board) -> Result<(), SendError> {
+        self.sender.send(board).await
+    }
 }
 
-pub enum Message {
-    Draw { grid: String },
-    Log { log: Log },
+/// The cells of a grid to render, row by row.
+#[derive(Clone, Debug, Default)]
+pub struct GridView {
+    pub cells: Vec<Vec<Cell>>,
+    /// The last attacked (x, y) coordinate, which is highlighted.
+    pub last_move: Option<(u8, u8)>,
 }
 
-#[derive(Clone)]
+impl GridView {
+    pub fn new(cells: Vec<Vec<Cell>>, last_move: Option<(u8, u8)>) -> Self {
+        Self { cells, last_move }
+    }
+}
+
+/// The log panels of a board.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Panel {
+    /// The results of the player's own moves.
+    Own,
+    /// The results of the opponent's moves.
+    Opponent,
+    /// Everything else, e.g. the game state or errors.
+    General,
+}
+
+#[derive(Clone, Debug)]
 pub struct Log {
     content: String,
     log_type: LogType,
@@ -30,6 +70,23 @@ impl Log {
     pub fn new(log_type: LogType, content: String) -> Log {
         Self { log_type, content }
     }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn log_type(&self) -> LogType {
+        self.log_type
+    }
+
+    /// Returns the panel the log is shown in.
+    pub fn panel(&self) -> Panel {
+        match self.log_type {
+            LogType::Hit | LogType::Miss => Panel::Own,
+            LogType::OpponentHit | LogType::OpponentMiss => Panel::Opponent,
+            _ => Panel::General,
+        }
+    }
 }
 
 impl<'a> From<Log> for Text<'a> {
@@ -50,7 +107,7 @@ impl<'a> From<Log> for Text<'a> {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum LogType {
     Debug,
     Error,
@@ -62,3 +119,50 @@ pub enum LogType {
     OpponentMiss,
     Won,
 }
+
+impl LogType {
+    /// Returns all available log types.
+    pub fn variants() -> [Self; 9] {
+        [
+            Self::Debug,
+            Self::Error,
+            Self::Hit,
+            Self::Info,
+            Self::Lost,
+            Self::Miss,
+            Self::OpponentHit,
+            Self::OpponentMiss,
+            Self::Won,
+        ]
+    }
+
+    /// Returns the level of the log type.
+    pub fn level(&self) -> LogLevel {
+        match self {
+            Self::Debug => LogLevel::Debug,
+            Self::Error => LogLevel::Error,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+/// The minimum level of the logs that are shown.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Error,
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "debug" => Ok(Self::Debug),
+            "info" => Ok(Self::Info),
+            "error" => Ok(Self::Error),
+            _ => Err(eyre::eyre!("unknown log level: {}", s)),
+        }
+    }
+}
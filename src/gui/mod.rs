@@ -1,5 +1,8 @@
 mod actor;
 mod ingress;
+mod theme;
+mod view;
 
 pub use actor::GuiActor;
-pub use ingress::{Log, LogType, Mailbox, Message};
+pub use ingress::{Board, GridView, Log, LogLevel, LogType, Mailbox, Panel};
+pub use theme::Theme;
@@ -0,0 +1,66 @@
+//! Color themes of the TUI.
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::game::Cell;
+
+/// The styles used to render the cells of the grids.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub empty: Style,
+    pub ship: Style,
+    pub hit: Style,
+    pub miss: Style,
+    pub sunk: Style,
+    /// Applied on top of the cell's style to highlight the last attacked cell.
+    pub last_move: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            empty: Style::new().fg(Color::DarkGray),
+            ship: Style::new().fg(Color::Cyan),
+            hit: Style::new().fg(Color::Red),
+            miss: Style::new().fg(Color::Blue),
+            sunk: Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            last_move: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+impl Theme {
+    /// Returns a theme using the colorblind-safe palette by Okabe and Ito.
+    pub fn colorblind() -> Self {
+        Self {
+            empty: Style::new().fg(Color::DarkGray),
+            ship: Style::new().fg(Color::Rgb(86, 180, 233)),
+            hit: Style::new().fg(Color::Rgb(230, 159, 0)),
+            miss: Style::new().fg(Color::Rgb(0, 114, 178)),
+            sunk: Style::new()
+                .fg(Color::Rgb(204, 121, 167))
+                .add_modifier(Modifier::BOLD),
+            last_move: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Returns the theme with the given name.
+    pub fn from_name(name: &str) -> eyre::Result<Self> {
+        match name.to_lowercase().as_str() {
+            "default" => Ok(Self::default()),
+            "colorblind" => Ok(Self::colorblind()),
+            _ => Err(eyre::eyre!("unknown theme: {}", name)),
+        }
+    }
+
+    /// Returns the style to render the given cell with.
+    pub fn style(&self, cell: Cell) -> Style {
+        match cell {
+            Cell::Empty => self.empty,
+            Cell::Ship(_) => self.ship,
+            Cell::Hit => self.hit,
+            Cell::Miss => self.miss,
+            Cell::Sunk => self.sunk,
+        }
+    }
+}
@@ -0,0 +1,1073 @@
+//! The view renders the received game state and handles the user's key presses,
+//! e.g. to scroll through, filter or search the logs.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::Range,
+};
+
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListDirection, ListItem, Paragraph, Tabs},
+};
+
+use crate::{
+    application::{MAX_CHAT_LENGTH, Sinking, Summary},
+    events::{Event, Side},
+    lobby::PeerStatus,
+};
+
+use super::{
+    ingress::{GridView, Log, LogType, Panel},
+    theme::Theme,
+};
+
+/// The maximum number of logs that are kept per panel.
+pub const MAX_LOGS: usize = 1_000;
+
+/// The maximum number of chat messages that are kept per board.
+const MAX_CHAT_MESSAGES: usize = 100;
+
+/// The number of logs that are scrolled with the page keys.
+const PAGE_SIZE: usize = 10;
+
+/// The log panels of every board, in the order they are focused.
+const PANELS: [Panel; 3] = [Panel::Own, Panel::Opponent, Panel::General];
+
+/// A bounded buffer of logs, which drops the oldest logs once the capacity is reached.
+pub struct LogBuffer {
+    logs: VecDeque<Log>,
+    capacity: usize,
+    /// The number of the newest (matching) logs that are scrolled past.
+    scroll: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(MAX_LOGS)
+    }
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            logs: VecDeque::with_capacity(capacity),
+            capacity,
+            scroll: 0,
+        }
+    }
+
+    pub fn push(&mut self, log: Log) {
+        if self.logs.len() == self.capacity {
+            self.logs.pop_front();
+        }
+
+        self.logs.push_back(log);
+    }
+
+    /// Returns the logs matching the filter, newest first.
+    pub fn matching<'a>(&'a self, filter: &'a Filter) -> impl Iterator<Item = &'a Log> {
+        self.logs.iter().rev().filter(|log| filter.matches(log))
+    }
+
+    /// Returns the matching logs that are visible with the current scroll position.
+    pub fn visible<'a>(&'a self, filter: &'a Filter) -> impl Iterator<Item = &'a Log> {
+        self.matching(filter).skip(self.scroll)
+    }
+
+    /// Scrolls by the given number of logs, where positive values scroll towards older logs.
+    ///
+    /// The scroll position is bounded by the number of matching logs.
+    pub fn scroll_by(&mut self, delta: isize, filter: &Filter) {
+        let max = self.matching(filter).count().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+}
+
+/// Filters the shown logs by their types and content.
+#[derive(Default)]
+pub struct Filter {
+    hidden: HashSet<LogType>,
+    search: String,
+}
+
+impl Filter {
+    pub fn matches(&self, log: &Log) -> bool {
+        !self.hidden.contains(&log.log_type())
+            && (self.search.is_empty()
+                || log
+                    .content()
+                    .to_lowercase()
+                    .contains(&self.search.to_lowercase()))
+    }
+
+    /// Shows or hides the logs of the given type.
+    pub fn toggle(&mut self, log_type: LogType) {
+        if !self.hidden.remove(&log_type) {
+            self.hidden.insert(log_type);
+        }
+    }
+}
+
+/// The state of a single board, as received from the game state actor.
+#[derive(Default)]
+struct BoardState {
+    /// The title of the board's tab.
+    title: String,
+    own_grid: GridView,
+    opponent_grid: GridView,
+    own_logs: LogBuffer,
+    opponent_logs: LogBuffer,
+    general_logs: LogBuffer,
+    /// The summary of the game, which is shown instead of the grids once the game is over.
+    summary: Option<Summary>,
+    /// The lobby, which is shown instead of the grids if the board receives its status.
+    lobby: Option<LobbyState>,
+    /// The chat messages of both players, oldest first.
+    chat: VecDeque<(Side, String)>,
+    /// Signals if the chat messages of the opponent are hidden.
+    muted: bool,
+}
+
+impl BoardState {
+    fn logs(&self, panel: Panel) -> &LogBuffer {
+        match panel {
+            Panel::Own => &self.own_logs,
+            Panel::Opponent => &self.opponent_logs,
+            Panel::General => &self.general_logs,
+        }
+    }
+
+    fn logs_mut(&mut self, panel: Panel) -> &mut LogBuffer {
+        match panel {
+            Panel::Own => &mut self.own_logs,
+            Panel::Opponent => &mut self.opponent_logs,
+            Panel::General => &mut self.general_logs,
+        }
+    }
+}
+
+/// The state of the lobby, as received from the lobby actor.
+#[derive(Default)]
+struct LobbyState {
+    peers: Vec<PeerStatus>,
+    /// The index of the selected peer.
+    selected: usize,
+}
+
+/// The areas to render the grids and logs of a single board into.
+struct BoardAreas {
+    own_grid: Rect,
+    own_logs: Rect,
+    opponent_grid: Rect,
+    opponent_logs: Rect,
+    general_logs: Rect,
+    chat: Rect,
+}
+
+/// The action that's requested by a key press, besides updating the view itself.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Saves the transcript of the game on the given board.
+    SaveTranscript(usize),
+    /// Requests a rematch for the game on the given board.
+    Rematch(usize),
+    /// Challenges the peer with the given index in the lobby.
+    Challenge(usize),
+    /// Accepts the challenge of the peer with the given index in the lobby.
+    Accept(usize),
+    /// Declines the challenge of the peer with the given index in the lobby.
+    Decline(usize),
+    /// Sends the chat message to the opponent of the game on the given board.
+    Chat(usize, String),
+}
+
+pub struct View {
+    boards: Vec<BoardState>,
+    theme: Theme,
+    filter: Filter,
+
+    /// Signals if only the active board is shown, with a tab for every board.
+    ///
+    /// Otherwise, all boards are rendered side by side (e.g. in self-play).
+    tabs: bool,
+
+    /// The index of the board that's shown in the tabbed mode.
+    active: usize,
+
+    /// The index of the focused log panel, counting the panels of all boards.
+    focus: usize,
+
+    /// Signals if the user is currently typing into the search box.
+    searching: bool,
+
+    /// The chat message the user is currently typing, if any.
+    chatting: Option<String>,
+}
+
+impl View {
+    pub fn new(boards: usize, theme: Theme) -> Self {
+        Self {
+            boards: (0..boards).map(|_| BoardState::default()).collect(),
+            theme,
+            filter: Filter::default(),
+            tabs: false,
+            active: 0,
+            focus: 0,
+            searching: false,
+            chatting: None,
+        }
+    }
+
+    /// Creates a view that shows one board at a time, where the boards are added
+    /// with [`View::add_board`] (e.g. once a game was started).
+    pub fn tabbed(theme: Theme) -> Self {
+        Self {
+            tabs: true,
+            ..Self::new(0, theme)
+        }
+    }
+
+    /// Adds a board with the given title, returning its index.
+    pub fn add_board(&mut self, title: String) -> usize {
+        self.boards.push(BoardState {
+            title,
+            ..BoardState::default()
+        });
+
+        self.boards.len() - 1
+    }
+
+    /// Updates the state of the given board with the received event.
+    pub fn handle_event(&mut self, board: usize, event: Event) {
+        let state = &mut self.boards[board];
+        match event {
+            Event::Lobby { peers } => {
+                let lobby = state.lobby.get_or_insert_with(LobbyState::default);
+                lobby.selected = lobby.selected.min(peers.len().saturating_sub(1));
+                lobby.peers = peers;
+            }
+            Event::Grids { own, opponent } => {
+                state.own_grid = own;
+                state.opponent_grid = opponent;
+            }
+            Event::Log { log } => state.logs_mut(log.panel()).push(log),
+            Event::Chat { by, text } => {
+                if by == Side::Opponent && state.muted {
+                    return;
+                }
+                if state.chat.len() == MAX_CHAT_MESSAGES {
+                    state.chat.pop_front();
+                }
+                state.chat.push_back((by, text));
+            }
+            Event::GameOver { summary } => state.summary = Some(summary),
+            // A new game of the series is started (e.g. after a rematch).
+            Event::Start { .. } => state.summary = None,
+            // NOTE: the game events are also published as logs, which are rendered instead.
+            _ => (),
+        };
+    }
+
+    /// Handles a key pressed by the user.
+    ///
+    /// - `tab` / `shift+tab`: focus the next / previous log panel
+    /// - `[` / `]`: show the previous / next board, in the tabbed mode
+    /// - `up` / `down`, `page up` / `page down`, `home` / `end`: scroll the focused log panel
+    /// - `1` - `9`: show or hide the logs of the corresponding type
+    /// - `/`: search the logs, `esc` clears the search
+    /// - `s` / `r`: save the transcript / request a rematch, once the focused board's game is over
+    /// - `t`: type a chat message to the opponent of the focused board, which is sent with `enter`
+    /// - `m`: mute or unmute the chat messages of the opponent of the focused board
+    /// - `k` / `j`, `c` / `a` / `d`: select a peer and challenge it / accept / decline its challenge,
+    ///   while the lobby's board is focused
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        // NOTE: in raw mode, Ctrl-C does not send an interrupt signal to the process,
+        // so we have to handle it ourselves.
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::Quit;
+        }
+
+        if self.searching {
+            match key.code {
+                KeyCode::Char(c) => self.filter.search.push(c),
+                KeyCode::Backspace => {
+                    self.filter.search.pop();
+                }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.filter.search.clear();
+                    self.searching = false;
+                }
+                _ => return Action::None,
+            }
+
+            self.reset_scroll();
+            return Action::None;
+        }
+
+        let board = self.focused_board();
+        if let Some(text) = self.chatting.as_mut() {
+            match key.code {
+                // NOTE: the length is limited while typing, since longer messages are rejected anyway.
+                KeyCode::Char(c) if text.chars().count() < MAX_CHAT_LENGTH => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    let text = self.chatting.take().unwrap_or_default();
+                    if !text.trim().is_empty() {
+                        return Action::Chat(board, text);
+                    }
+                }
+                KeyCode::Esc => self.chatting = None,
+                _ => (),
+            }
+
+            return Action::None;
+        }
+
+        if let Some(lobby) = self
+            .boards
+            .get_mut(board)
+            .and_then(|state| state.lobby.as_mut())
+        {
+            match key.code {
+                // NOTE: the arrow keys still scroll the focused log panel of the lobby's board.
+                KeyCode::Char('k') => {
+                    lobby.selected = lobby.selected.saturating_sub(1);
+                    return Action::None;
+                }
+                KeyCode::Char('j') => {
+                    lobby.selected = (lobby.selected + 1).min(lobby.peers.len().saturating_sub(1));
+                    return Action::None;
+                }
+                KeyCode::Char('c') => return Action::Challenge(lobby.selected),
+                KeyCode::Char('a') => return Action::Accept(lobby.selected),
+                KeyCode::Char('d') => return Action::Decline(lobby.selected),
+                _ => (),
+            }
+        }
+
+        // NOTE: only the panels of the shown boards can be focused.
+        let visible = self.visible_boards();
+        let (first, panels) = (visible.start * PANELS.len(), visible.len() * PANELS.len());
+        match key.code {
+            KeyCode::Tab if panels > 0 => self.focus = first + (self.focus - first + 1) % panels,
+            KeyCode::BackTab if panels > 0 => {
+                self.focus = first + (self.focus - first + panels - 1) % panels
+            }
+            KeyCode::Char('[') if self.tabs => self.switch_tab(-1),
+            KeyCode::Char(']') if self.tabs => self.switch_tab(1),
+            KeyCode::Up => self.scroll_focused(-1),
+            KeyCode::Down => self.scroll_focused(1),
+            KeyCode::PageUp => self.scroll_focused(-(PAGE_SIZE as isize)),
+            KeyCode::PageDown => self.scroll_focused(PAGE_SIZE as isize),
+            KeyCode::Home => self.scroll_focused(-(MAX_LOGS as isize)),
+            KeyCode::End => self.scroll_focused(MAX_LOGS as isize),
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Char('s') if self.focused_summary().is_some() => {
+                return Action::SaveTranscript(self.focused_board());
+            }
+            KeyCode::Char('r') if self.focused_summary().is_some() => {
+                return Action::Rematch(self.focused_board());
+            }
+            KeyCode::Char('/') => self.searching = true,
+            // NOTE: the lobby's board has no opponent to chat with.
+            KeyCode::Char('t') if self.boards.get(board).is_some_and(|s| s.lobby.is_none()) => {
+                self.chatting = Some(String::new())
+            }
+            KeyCode::Char('m') => {
+                if let Some(state) = self.boards.get_mut(board).filter(|s| s.lobby.is_none()) {
+                    state.muted = !state.muted;
+                }
+            }
+            KeyCode::Esc => {
+                self.filter.search.clear();
+                self.reset_scroll();
+            }
+            KeyCode::Char(c) => {
+                if let Some(log_type) = c
+                    .to_digit(10)
+                    .and_then(|n| LogType::variants().get((n as usize).checked_sub(1)?).copied())
+                {
+                    self.filter.toggle(log_type);
+                    self.reset_scroll();
+                }
+            }
+            _ => (),
+        }
+
+        Action::None
+    }
+
+    /// Returns the indices of the boards that are shown.
+    fn visible_boards(&self) -> Range<usize> {
+        match self.tabs {
+            true => self.active..(self.active + 1).min(self.boards.len()),
+            false => 0..self.boards.len(),
+        }
+    }
+
+    /// Shows the board with the given offset to the active board, keeping the focused panel.
+    fn switch_tab(&mut self, delta: isize) {
+        if self.boards.is_empty() {
+            return;
+        }
+
+        let boards = self.boards.len() as isize;
+        self.active = (self.active as isize + delta).rem_euclid(boards) as usize;
+        self.focus = self.active * PANELS.len() + self.focus % PANELS.len();
+    }
+
+    /// Returns the index of the board that contains the focused log panel.
+    fn focused_board(&self) -> usize {
+        self.focus / PANELS.len()
+    }
+
+    fn focused_summary(&self) -> Option<&Summary> {
+        self.boards.get(self.focused_board())?.summary.as_ref()
+    }
+
+    fn scroll_focused(&mut self, delta: isize) {
+        let (board, panel) = (self.focused_board(), PANELS[self.focus % PANELS.len()]);
+        if let Some(state) = self.boards.get_mut(board) {
+            state.logs_mut(panel).scroll_by(delta, &self.filter);
+        }
+    }
+
+    /// Resets the scroll positions, since they are relative to the matching logs.
+    fn reset_scroll(&mut self) {
+        for state in self.boards.iter_mut() {
+            for panel in PANELS {
+                state.logs_mut(panel).scroll = 0;
+            }
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [tabs_area, boards_area, status_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(if self.tabs { 1 } else { 0 }),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .areas::<3>(frame.area());
+
+        if self.tabs {
+            frame.render_widget(self.draw_tabs(), tabs_area);
+        }
+
+        let visible = self.visible_boards();
+        if visible.is_empty() {
+            frame.render_widget(
+                Paragraph::new("waiting for a game to start...")
+                    .style(Style::new().fg(Color::DarkGray)),
+                boards_area,
+            );
+        }
+
+        for (board, areas) in visible
+            .clone()
+            .zip(create_layout(boards_area, visible.len()))
+        {
+            self.draw_board(frame, board, &self.boards[board], areas);
+        }
+
+        frame.render_widget(self.status_line(), status_area);
+    }
+
+    /// Renders the titles of all boards, highlighting the active one.
+    fn draw_tabs(&self) -> Tabs<'_> {
+        Tabs::new(self.boards.iter().map(|state| state.title.as_str()))
+            .select(self.active)
+            .highlight_style(Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+    }
+
+    /// Draws the grids and log panels of a single board into the given areas.
+    ///
+    /// The own grid and the view of the opponent's grid are each shown next to
+    /// the logs of the corresponding moves, with the general logs below:
+    ///
+    /// |------------------------|
+    /// |.|-------|...|--------|.|
+    /// |.|..Grid.|...|..Logs..|.|
+    /// |.|-------|...|--------|.|
+    /// |.|..Opps.|...|..Logs..|.|
+    /// |.|-------|...|--------|.|
+    /// |-|--------------------|-|
+    /// |-|.General..|...Chat..|-|
+    /// |-|--------------------|-|
+    /// |------------------------|
+    fn draw_board(&self, frame: &mut Frame, board: usize, state: &BoardState, areas: BoardAreas) {
+        let focused = |panel: Panel| self.focus == board * PANELS.len() + panel as usize;
+
+        // The lobby's board has no chat, so the general logs take its place.
+        let general_logs = match state.lobby {
+            Some(_) => areas.general_logs.union(areas.chat),
+            None => {
+                frame.render_widget(draw_chat(state), areas.chat);
+                areas.general_logs
+            }
+        };
+        frame.render_widget(
+            self.put_logs("General", state.logs(Panel::General), focused(Panel::General)),
+            general_logs,
+        );
+
+        if let Some(summary) = &state.summary {
+            return self.draw_game_over(frame, summary, areas);
+        }
+
+        if let Some(lobby) = &state.lobby {
+            return frame.render_widget(
+                draw_lobby(lobby),
+                areas
+                    .own_grid
+                    .union(areas.own_logs)
+                    .union(areas.opponent_grid)
+                    .union(areas.opponent_logs),
+            );
+        }
+
+        frame.render_widget(self.draw_grid("Own Grid", &state.own_grid), areas.own_grid);
+        frame.render_widget(
+            self.put_logs("Own Moves", state.logs(Panel::Own), focused(Panel::Own)),
+            areas.own_logs,
+        );
+        frame.render_widget(
+            self.draw_grid("Opponent Grid", &state.opponent_grid),
+            areas.opponent_grid,
+        );
+        frame.render_widget(
+            self.put_logs(
+                "Opponent Moves",
+                state.logs(Panel::Opponent),
+                focused(Panel::Opponent),
+            ),
+            areas.opponent_logs,
+        );
+    }
+
+    /// Draws the game-over screen of a single board, replacing the grids and move logs
+    /// with both fully revealed boards and the statistics of the game.
+    fn draw_game_over(&self, frame: &mut Frame, summary: &Summary, areas: BoardAreas) {
+        let [own_board, opponent_board] = split_horizontal(areas.own_grid.union(areas.own_logs));
+
+        frame.render_widget(
+            self.draw_grid("Own Board", &GridView::new(summary.own_board.clone(), None)),
+            own_board,
+        );
+        frame.render_widget(
+            self.draw_grid(
+                "Opponent Board",
+                &GridView::new(summary.opponent_board.clone(), None),
+            ),
+            opponent_board,
+        );
+        frame.render_widget(
+            draw_summary(summary),
+            areas.opponent_grid.union(areas.opponent_logs),
+        );
+    }
+
+    /// Renders the given grid, styling every cell based on the configured theme.
+    ///
+    /// The layout matches the one of `Grid::as_string`, i.e. the columns
+    /// are labeled with letters and the rows with numbers.
+    fn draw_grid<'a>(&self, title: &'a str, grid: &GridView) -> Paragraph<'a> {
+        let block = Block::default().title(title).borders(Borders::ALL);
+
+        let width = grid.cells.first().map_or(0, |row| row.len());
+        let mut lines = vec![
+            Line::default(),
+            Line::raw(
+                (0..width)
+                    .map(|x| format!("{} ", char::from(b'A' + x as u8)))
+                    .fold("   ".to_string(), |header, column| header + &column),
+            ),
+        ];
+
+        for (y, row) in grid.cells.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!("{:<3}", y + 1))];
+            for (x, cell) in row.iter().enumerate() {
+                let mut style = self.theme.style(*cell);
+                if grid.last_move == Some((x as u8 + 1, y as u8 + 1)) {
+                    style = style.patch(self.theme.last_move);
+                }
+
+                spans.push(Span::styled(cell.symbol(), style));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        Paragraph::new(Text::from(lines)).block(block)
+    }
+
+    fn put_logs<'a>(&'a self, title: &str, logs: &'a LogBuffer, focused: bool) -> List<'a> {
+        let title = match logs.scroll {
+            0 => title.to_string(),
+            scroll => format!("{} (+{})", title, scroll),
+        };
+        let border_style = match focused {
+            true => Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            false => Style::new(),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(border_style);
+
+        let items = logs
+            .visible(&self.filter)
+            .map(|log| ListItem::new(log.to_owned()));
+        List::new(items).block(block)
+    }
+
+    /// Returns the status line, showing either the search box, the chat message being typed
+    /// or the available keys.
+    fn status_line(&self) -> Paragraph<'_> {
+        if self.searching {
+            return Paragraph::new(format!("/{}_", self.filter.search));
+        }
+        if let Some(text) = &self.chatting {
+            return Paragraph::new(format!("say: {}_", text));
+        }
+
+        let hidden = LogType::variants()
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| self.filter.hidden.contains(t))
+            .map(|(i, t)| format!("{}:{:?}", i + 1, t))
+            .collect::<Vec<String>>();
+
+        let mut status = "q: quit | tab: focus | ↑↓ pgup pgdn home end: scroll | 1-9: toggle log types | /: search"
+            .to_string();
+        if self.tabs {
+            status.push_str(" | [ ]: switch board");
+        }
+        status.push_str(" | t: chat | m: mute");
+        if !hidden.is_empty() {
+            status.push_str(&format!(" | hidden: {}", hidden.join(", ")));
+        }
+        if !self.filter.search.is_empty() {
+            status.push_str(&format!(" | search: {}", self.filter.search));
+        }
+
+        Paragraph::new(status).style(Style::new().fg(Color::DarkGray))
+    }
+}
+
+/// Renders the statistics of a finished game, together with the available options.
+fn draw_summary(summary: &Summary) -> Paragraph<'static> {
+    let result = match summary.winner {
+        Side::Own => "👑 you won the game",
+        Side::Opponent => "💔 you lost the game",
+    };
+    let duration = summary.duration.as_secs();
+    let row = |name: &str, own: String, opponent: String| {
+        Line::raw(format!("{:<16} {:>8} {:>8}", name, own, opponent))
+    };
+    let sinkings = |sinkings: &[Sinking]| {
+        sinkings
+            .iter()
+            .map(|s| match s.shots {
+                Some(shots) => format!("{} {}", s.ship, shots),
+                None => format!("{} -", s.ship),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    let (own, opponent) = (&summary.own, &summary.opponent);
+
+    let mut lines = vec![
+        Line::styled(result, Style::new().add_modifier(Modifier::BOLD)),
+        Line::raw(format!("duration: {}m {:02}s", duration / 60, duration % 60)),
+        Line::raw(format!(
+            "series: you {} - {} opponent",
+            summary.series.wins, summary.series.losses
+        )),
+        Line::default(),
+        row("", "you".into(), "opponent".into()),
+        row("shots fired", own.shots.to_string(), opponent.shots.to_string()),
+        row("hits", own.hits.to_string(), opponent.hits.to_string()),
+        row(
+            "hit rate",
+            format!("{:.0}%", own.hit_rate * 100.0),
+            format!("{:.0}%", opponent.hit_rate * 100.0),
+        ),
+        row(
+            "longest streak",
+            own.longest_streak.to_string(),
+            opponent.longest_streak.to_string(),
+        ),
+        Line::default(),
+        Line::raw(format!("shots to sink the opponent's ships: {}", sinkings(&own.sinkings))),
+        Line::raw(format!("shots to sink your ships: {}", sinkings(&opponent.sinkings))),
+        Line::default(),
+        Line::styled(
+            "s: save transcript | r: request rematch | q: quit",
+            Style::new().fg(Color::Yellow),
+        ),
+    ];
+    if summary.disputed {
+        lines.insert(
+            1,
+            Line::styled(
+                "⚠️ disputed: the opponent's revealed ships were rejected",
+                Style::new().fg(Color::Red),
+            ),
+        );
+    }
+
+    let block = Block::default().title("Game Over").borders(Borders::ALL);
+    Paragraph::new(Text::from(lines)).block(block)
+}
+
+/// Renders the chat messages of a board, newest at the bottom.
+fn draw_chat(state: &BoardState) -> List<'static> {
+    let title = match state.muted {
+        true => "Chat (muted)",
+        false => "Chat",
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    let items = state.chat.iter().rev().map(|(by, text)| {
+        let (name, color) = match by {
+            Side::Own => ("you", Color::Cyan),
+            Side::Opponent => ("opponent", Color::Magenta),
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{}: ", name), Style::new().fg(color)),
+            Span::raw(text.clone()),
+        ]))
+    });
+
+    // NOTE: the list is rendered from the bottom, so that the newest messages are always visible.
+    List::new(items)
+        .block(block)
+        .direction(ListDirection::BottomToTop)
+}
+
+/// Renders the known peers of the lobby with their online status, pending challenges and running games.
+fn draw_lobby(lobby: &LobbyState) -> Paragraph<'static> {
+    let mut lines = vec![Line::default()];
+
+    for (index, peer) in lobby.peers.iter().enumerate() {
+        let status = match peer.online {
+            true => Span::styled("● online ", Style::new().fg(Color::Green)),
+            false => Span::styled("○ offline", Style::new().fg(Color::DarkGray)),
+        };
+        let challenge = match (peer.playing, peer.challenge) {
+            (true, _) => "🎮 playing",
+            (false, Some(Side::Own)) => "challenged; waiting for the answer",
+            (false, Some(Side::Opponent)) => "⚔️ challenges you",
+            (false, None) => "",
+        };
+
+        let mut line = Line::from(vec![
+            Span::raw(if index == lobby.selected { "> " } else { "  " }),
+            status,
+            Span::raw(format!("  {}  {}", peer.public_key, challenge)),
+        ]);
+        if index == lobby.selected {
+            line = line.style(Style::new().add_modifier(Modifier::BOLD));
+        }
+        lines.push(line);
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::styled(
+        "j/k: select peer | c: challenge | a: accept | d: decline | q: quit",
+        Style::new().fg(Color::Yellow),
+    ));
+
+    let block = Block::default().title("Lobby").borders(Borders::ALL);
+    Paragraph::new(Text::from(lines)).block(block)
+}
+
+/// Creates the areas for the grids and logs of every board.
+///
+/// Multiple boards (e.g. in self-play) are rendered side by side.
+fn create_layout(area: Rect, boards: usize) -> Vec<BoardAreas> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, boards as u32); boards])
+        .split(area)
+        .iter()
+        .map(|column| {
+            let [own, opponent, general_logs] = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(20),
+                ])
+                .areas::<3>(*column);
+            let [own_grid, own_logs] = split_horizontal(own);
+            let [opponent_grid, opponent_logs] = split_horizontal(opponent);
+            let [general_logs, chat] = split_horizontal(general_logs);
+
+            BoardAreas {
+                own_grid,
+                own_logs,
+                opponent_grid,
+                opponent_logs,
+                general_logs,
+                chat,
+            }
+        })
+        .collect()
+}
+
+/// Splits the given area into two halves next to each other.
+fn split_horizontal(area: Rect) -> [Rect; 2] {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .areas::<2>(area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::{Score, Stats};
+
+    #[test]
+    fn test_log_buffer() {
+        let mut buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(Log::new(LogType::Info, format!("log {}", i)));
+        }
+        assert_eq!(3, buffer.logs.len());
+
+        let filter = Filter::default();
+        let contents = buffer
+            .visible(&filter)
+            .map(|log| log.content())
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["log 4", "log 3", "log 2"], contents);
+
+        buffer.scroll_by(1, &filter);
+        assert_eq!(Some("log 3"), buffer.visible(&filter).next().map(|l| l.content()));
+
+        // the scroll position is bounded by the number of logs
+        buffer.scroll_by(10, &filter);
+        assert_eq!(2, buffer.scroll);
+        buffer.scroll_by(-10, &filter);
+        assert_eq!(0, buffer.scroll);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut filter = Filter::default();
+        let hit = Log::new(LogType::Hit, "A1: attack hit".into());
+        let debug = Log::new(LogType::Debug, "sending message".into());
+
+        assert!(filter.matches(&hit) && filter.matches(&debug));
+
+        filter.toggle(LogType::Debug);
+        assert!(filter.matches(&hit) && !filter.matches(&debug));
+        filter.toggle(LogType::Debug);
+        assert!(filter.matches(&debug));
+
+        filter.search = "a1".into();
+        assert!(filter.matches(&hit) && !filter.matches(&debug));
+    }
+
+    #[test]
+    fn test_quit_keys() {
+        let mut view = View::new(1, Theme::default());
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert_eq!(Action::Quit, view.handle_key(key(KeyCode::Char('q'))));
+        assert_eq!(
+            Action::Quit,
+            view.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+
+        // while searching, `q` is part of the search term
+        assert_eq!(Action::None, view.handle_key(key(KeyCode::Char('/'))));
+        assert_eq!(Action::None, view.handle_key(key(KeyCode::Char('q'))));
+        assert_eq!("q", view.filter.search);
+    }
+
+    #[test]
+    fn test_game_over_keys() {
+        let mut view = View::new(1, Theme::default());
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+        // the options are only available once the game is over
+        assert_eq!(Action::None, view.handle_key(key('s')));
+        assert_eq!(Action::None, view.handle_key(key('r')));
+
+        let stats = Stats::new(&[], &[]);
+        view.handle_event(
+            0,
+            Event::GameOver {
+                summary: Summary {
+                    winner: Side::Own,
+                    duration: std::time::Duration::from_secs(65),
+                    own: stats.clone(),
+                    opponent: stats,
+                    series: Score::default(),
+                    own_board: Vec::new(),
+                    opponent_board: Vec::new(),
+                    disputed: false,
+                },
+            },
+        );
+
+        assert_eq!(Action::SaveTranscript(0), view.handle_key(key('s')));
+        assert_eq!(Action::Rematch(0), view.handle_key(key('r')));
+    }
+
+    #[test]
+    fn test_lobby_keys() {
+        let mut view = View::new(2, Theme::default());
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let peer = |challenge| PeerStatus {
+            public_key: "peer".into(),
+            online: true,
+            challenge,
+            playing: false,
+        };
+
+        // without a lobby, the keys are not handled
+        assert_eq!(Action::None, view.handle_key(key(KeyCode::Char('c'))));
+
+        view.handle_event(
+            0,
+            Event::Lobby {
+                peers: vec![peer(None), peer(Some(Side::Opponent))],
+            },
+        );
+        assert_eq!(Action::Challenge(0), view.handle_key(key(KeyCode::Char('c'))));
+
+        // the arrow keys scroll the focused log panel instead of moving the selection
+        view.handle_key(key(KeyCode::Down));
+        assert_eq!(
+            Action::Challenge(0),
+            view.handle_key(key(KeyCode::Char('c')))
+        );
+
+        // the selection is bounded by the number of peers
+        view.handle_key(key(KeyCode::Char('j')));
+        view.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(Action::Accept(1), view.handle_key(key(KeyCode::Char('a'))));
+        assert_eq!(Action::Decline(1), view.handle_key(key(KeyCode::Char('d'))));
+
+        // the keys are only handled while the lobby's board is focused
+        for _ in 0..PANELS.len() {
+            view.handle_key(key(KeyCode::Tab));
+        }
+        assert_eq!(Action::None, view.handle_key(key(KeyCode::Char('c'))));
+    }
+
+    #[test]
+    fn test_chat_keys() {
+        let mut view = View::new(2, Theme::default());
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        // while chatting, the keys are part of the message
+        view.handle_key(key(KeyCode::Char('t')));
+        for c in "gg q".chars() {
+            assert_eq!(Action::None, view.handle_key(key(KeyCode::Char(c))));
+        }
+        view.handle_key(key(KeyCode::Backspace));
+        assert_eq!(
+            Action::Chat(0, "gg ".into()),
+            view.handle_key(key(KeyCode::Enter))
+        );
+        assert_eq!(None, view.chatting);
+
+        // empty messages are not sent, and `esc` discards the message
+        view.handle_key(key(KeyCode::Char('t')));
+        assert_eq!(Action::None, view.handle_key(key(KeyCode::Enter)));
+        view.handle_key(key(KeyCode::Char('t')));
+        view.handle_key(key(KeyCode::Char('a')));
+        view.handle_key(key(KeyCode::Esc));
+        assert_eq!(None, view.chatting);
+
+        // the length of the message is limited while typing
+        view.handle_key(key(KeyCode::Char('t')));
+        for _ in 0..=MAX_CHAT_LENGTH {
+            view.handle_key(key(KeyCode::Char('a')));
+        }
+        assert_eq!(
+            Action::Chat(0, "a".repeat(MAX_CHAT_LENGTH)),
+            view.handle_key(key(KeyCode::Enter))
+        );
+
+        // the opponent's messages are dropped while muted
+        let chat = |by, text: &str| Event::Chat {
+            by,
+            text: text.into(),
+        };
+        view.handle_event(0, chat(Side::Opponent, "hi"));
+        view.handle_key(key(KeyCode::Char('m')));
+        view.handle_event(0, chat(Side::Opponent, "spam"));
+        view.handle_event(0, chat(Side::Own, "bye"));
+        assert_eq!(
+            vec!["hi", "bye"],
+            view.boards[0]
+                .chat
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert!(!view.boards[1].muted);
+
+        // the lobby's board has no chat
+        view.handle_event(1, Event::Lobby { peers: Vec::new() });
+        for _ in 0..PANELS.len() {
+            view.handle_key(key(KeyCode::Tab));
+        }
+        view.handle_key(key(KeyCode::Char('t')));
+        assert_eq!(None, view.chatting);
+    }
+
+    #[test]
+    fn test_tabs() {
+        let mut view = View::tabbed(Theme::default());
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        // without any boards, the keys are ignored
+        view.handle_key(key(KeyCode::Tab));
+        view.handle_key(key(KeyCode::Char(']')));
+        view.handle_key(key(KeyCode::Down));
+        assert_eq!((0, 0), (view.active, view.focus));
+
+        for title in ["Lobby", "Game 1", "Game 2"] {
+            view.add_board(title.into());
+        }
+        assert_eq!(0..1, view.visible_boards());
+
+        // the focus cycles through the panels of the shown board only
+        for _ in 0..PANELS.len() {
+            view.handle_key(key(KeyCode::Tab));
+        }
+        assert_eq!(0, view.focus);
+        view.handle_key(key(KeyCode::BackTab));
+        assert_eq!(PANELS.len() - 1, view.focus);
+
+        // switching the board keeps the focused panel
+        view.handle_key(key(KeyCode::Char(']')));
+        assert_eq!(1..2, view.visible_boards());
+        assert_eq!(2 * PANELS.len() - 1, view.focus);
+
+        view.handle_key(key(KeyCode::Char('[')));
+        view.handle_key(key(KeyCode::Char('[')));
+        assert_eq!(2, view.active);
+        assert_eq!(2, view.focused_board());
+    }
+}
@@ -48,6 +48,95 @@
 /// RUST_LOG=info cargo run --bin player -- --public-key 478b8e507e0bb2b18c0f9e0824769e8562d10df9abe2e774896f82b4b4405266
 /// ```
 ///
+/// The setup accepts multiple known peers by repeating the `--peer-endpoint` and `--peer-public-key` flags.
+/// Without further flags, the game is played against the first known peer.
+/// In the lobby mode, the online status of all known peers is shown, and any of them
+/// can be challenged to a game (or their challenges accepted or declined):
+///
+/// ```shell
+/// RUST_LOG=info cargo run --bin player -- --public-key 478b8e507e0bb2b18c0f9e0824769e8562d10df9abe2e774896f82b4b4405266 --lobby
+/// ```
+///
+/// For bots, a known peer can be challenged automatically once it's online with `--challenge <PUBKEY>`,
+/// and all challenges can be accepted automatically with `--accept-challenges`.
+///
+/// Every accepted challenge starts another game, so that several games against different peers
+/// are played at the same time over the same p2p network. The TUI shows a tab for the lobby and
+/// every game, which are switched with `[` and `]`.
+///
+/// The game messages are sent with sequence numbers and acknowledged by the opponent.
+/// Lost messages are retransmitted with an exponential backoff, while duplicates are dropped,
+/// so that a lossy connection only delays the game instead of deadlocking it.
+/// The chat messages are sent best-effort instead, so that a lost chat message can't hold back the moves.
+///
+/// During a game, short text messages can be sent to the opponent by pressing `t` in the TUI,
+/// which are shown in the chat pane next to the general logs. The messages are limited in their
+/// length and rate, and the opponent's messages can be muted with `m`.
+///
+/// Which of the known peers are accepted as opponents can be restricted during the setup,
+/// by allowing only some peers with `--allow <PUBKEY>`, blocking peers with `--block <PUBKEY>`,
+/// or playing against only a single opponent at a time with `--single-opponent`.
+/// The connections of all other peers are rejected, as well as any game messages of peers
+/// that are not an opponent.
+///
+/// The LLM model to use can be selected with the `--model` flag.
+/// In case no model is installed, an offline mock model can be used instead,
+/// which either replays canned responses from a file (`--mock-responses`)
+/// or returns random moves with a configurable rate of malformed responses (`--mock-malformed-rate`):
+///
+/// ```shell
+/// RUST_LOG=info cargo run --bin player -- --public-key 478b8e507e0bb2b18c0f9e0824769e8562d10df9abe2e774896f82b4b4405266 --model mock --mock-malformed-rate 0.2
+/// ```
+///
+/// For bots or CI runs, the player can also be run without the TUI.
+/// In headless mode, all game events (handshake, attacks, results, sunk ships, the game end and errors)
+/// are written as newline-delimited JSON to stdout or the file given with `--events`,
+/// and the process exits once the game ended:
+///
+/// ```shell
+/// cargo run --bin player -- --public-key 478b8e507e0bb2b18c0f9e0824769e8562d10df9abe2e774896f82b4b4405266 --model mock --headless
+/// ```
+///
+/// By default, all players run on the same host. To play against a peer on another host of the LAN,
+/// the address at which the player can be dialed and the production preset of the p2p network
+/// are set during the setup, where the known peers are configured with their LAN endpoints.
+/// Additional nodes to discover the peers can be added with `--bootstrapper-endpoint` and
+/// `--bootstrapper-public-key`:
+///
+/// ```shell
+/// cargo run --bin setup -- --private-key <PRIVATE_KEY> --port 5670 --peer-endpoint 192.168.1.3:5670 --peer-public-key <PEER_PUBKEY> --listen 0.0.0.0:5670 --dialable 192.168.1.2:5670 --production --allow-private-ips
+/// ```
+///
+/// The rate limits and backlogs of the p2p channels, as well as the maximum message size, are taken
+/// from the preset of the game mode, which is set with `--channel-preset` during the setup
+/// (`interactive` for games in the TUI or `bot` for headless bots). Every value can be overridden
+/// in the `channels` section of the configuration file.
+///
+/// The games of the players can be watched live by a spectator node, whose public key has to be
+/// added to the players' configurations with `--spectator <PUBKEY>` during the setup.
+/// The spectator's own configuration lists the players as its known peers.
+/// The ships are only revealed to the spectators once the game is over:
+///
+/// ```shell
+/// cargo run --bin spectator -- --public-key <SPECTATOR_PUBKEY>
+/// ```
+///
+/// To watch a game without setting up two players, both players can also be run
+/// in a single process, which are then connected through a simulated p2p network.
+/// The strategies of both players can be chosen independently:
+///
+/// ```shell
+/// cargo run --bin selfplay -- --first hunt --second mock
+/// ```
+///
+/// To compare the strategies against each other, a headless tournament can be run,
+/// which plays a number of seeded games between every pair of strategies
+/// and reports the win rates and shots needed to win (with 95% confidence intervals):
+///
+/// ```shell
+/// cargo run --release --bin tournament -- --strategies hunt,random,density,mock --games 1000 --json results.json
+/// ```
+///
 /// ## Implementation Steps
 ///
 /// - Start a simple setup where commonware-p2p communicates between two nodes (two keys).
@@ -64,6 +153,7 @@
 /// -  Ultimately, there should be some connection request logic implemented,
 ///    where a new peer is only accepted in case there is not an established peer
 ///    connection already, and the peer is in a list of whitelisted addresses.
+///    (This is done by the configurable [`lobby::Policy`].)
 ///
 /// - This can be extended to incorporate moves for the battleship game.
 ///   As a first iteration, just shoot at increasing fields A1, B1, ... .
@@ -79,7 +169,12 @@
 ///
 pub mod application;
 pub mod config;
+pub mod events;
 pub mod game;
 pub mod gui;
+pub mod lobby;
+pub mod spectator;
+pub mod strategy;
+pub mod tournament;
 
 pub use config::{Config, get_config_path};
@@ -0,0 +1,774 @@
+use std::time::{Duration, SystemTime};
+
+use commonware_cryptography::PublicKey;
+use commonware_macros::select;
+use commonware_p2p::{Receiver, Recipients, Sender};
+use commonware_runtime::{Clock, ContextCell, Spawner, spawn_cell};
+use eyre::Context;
+use futures::{SinkExt, StreamExt, channel::mpsc, stream};
+
+use crate::{
+    events::{Event, EventSink, Side},
+    gui::{Log, LogType},
+};
+
+use super::{
+    Policy,
+    ingress::{Command, Mailbox, Message, PeerStatus},
+};
+
+/// The interval at which the node announces its presence to the other peers.
+const PRESENCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The duration after which a peer is shown as offline, if it did not announce its presence.
+const ONLINE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The state of a known peer in the lobby.
+struct Peer<P: PublicKey> {
+    public_key: P,
+    /// The time at which the last message of the peer was received.
+    last_seen: Option<SystemTime>,
+    /// The player that sent the pending challenge, if any.
+    challenge: Option<Side>,
+    /// Signals if a game against the peer is running, during which its challenges are declined.
+    playing: bool,
+}
+
+/// The lobby actor announces the node's presence, tracks the online status of the known peers
+/// and exchanges the challenges with them.
+///
+/// Every accepted challenge starts a new game, so that several games can be played at once.
+pub struct LobbyActor<R: Spawner + Clock, P: PublicKey> {
+    context: ContextCell<R>,
+
+    // The status of the lobby is published to all subscribers of the sink (e.g. the GUI actor).
+    events: EventSink,
+
+    /// The known peers that are allowed by the policy, in the order of the configuration.
+    peers: Vec<Peer<P>>,
+
+    /// The policy which peers are accepted as opponents.
+    policy: Policy<P>,
+
+    /// The last published status of the peers, to only publish changes.
+    published: Vec<PeerStatus>,
+
+    /// Signals if all challenges are accepted automatically (e.g. in headless mode).
+    accept_challenges: bool,
+
+    /// The peer that's challenged automatically once it's online, if any.
+    auto_challenge: Option<P>,
+
+    /// The commands received e.g. from the GUI.
+    ///
+    /// NOTE: this is taken out of the actor once it's running, so that it can be polled
+    /// while handling the commands.
+    commands: Option<mpsc::Receiver<Command>>,
+
+    /// The opponents are sent through this channel once a challenge was accepted.
+    matched: mpsc::Sender<P>,
+
+    /// The opponents of the finished games are received through this channel (if any).
+    ///
+    /// NOTE: this is taken out of the actor once it's running, like the commands.
+    finished: Option<mpsc::UnboundedReceiver<P>>,
+}
+
+impl<R: Spawner + Clock, P: PublicKey> LobbyActor<R, P> {
+    /// Creates a new lobby actor for the given known peers.
+    ///
+    /// The peers that are not allowed by the policy are left out of the lobby, so that their
+    /// messages are ignored and they can't be challenged.
+    ///
+    /// Returns the mailbox to send commands to the actor, as well as the receiver
+    /// of the opponents to start a game against.
+    pub fn new(
+        context: R,
+        events: EventSink,
+        peers: Vec<P>,
+        policy: Policy<P>,
+        accept_challenges: bool,
+        auto_challenge: Option<P>,
+    ) -> (Self, Mailbox, mpsc::Receiver<P>) {
+        let (sender, receiver) = mpsc::channel(1);
+        let (matched_sender, matched_receiver) = mpsc::channel(1);
+
+        let actor = Self {
+            context: ContextCell::new(context),
+            events,
+            peers: peers
+                .into_iter()
+                .filter(|public_key| policy.allows(public_key))
+                .map(|public_key| Peer {
+                    public_key,
+                    last_seen: None,
+                    challenge: None,
+                    playing: false,
+                })
+                .collect(),
+            published: Vec::new(),
+            policy,
+            accept_challenges,
+            auto_challenge,
+            commands: Some(receiver),
+            matched: matched_sender,
+            finished: None,
+        };
+
+        (actor, Mailbox::new(sender), matched_receiver)
+    }
+
+    /// Frees the opponents of the finished games received through the given channel,
+    /// so that they can be challenged again (e.g. from the game manager).
+    pub fn with_finished(mut self, finished: mpsc::UnboundedReceiver<P>) -> Self {
+        self.finished = Some(finished);
+        self
+    }
+
+    pub fn start(
+        mut self,
+        sender: impl Sender<PublicKey = P>,
+        receiver: impl Receiver<PublicKey = P>,
+    ) {
+        spawn_cell!(self.context, self.run(sender, receiver).await);
+    }
+
+    async fn run(
+        mut self,
+        mut sender: impl Sender<PublicKey = P>,
+        mut receiver: impl Receiver<PublicKey = P>,
+    ) {
+        // NOTE: the commands are chained with a pending stream, so that the loop is not woken up
+        // repeatedly once all mailboxes are dropped (e.g. in headless mode).
+        let mut commands = self
+            .commands
+            .take()
+            .expect("actor must only be run once")
+            .chain(stream::pending());
+
+        // NOTE: without a channel of the finished games, the peers stay busy once their game started.
+        let mut finished = stream::iter(self.finished.take())
+            .flatten()
+            .chain(stream::pending());
+
+        let mut stopped = self.context.as_present().stopped();
+
+        // NOTE: the presence is announced at fixed times, so that the announcements are not
+        // delayed indefinitely by the messages of the other peers.
+        let clock = self.context.as_present().clone();
+        let mut next_presence = clock.current();
+
+        loop {
+            select! {
+                _ = &mut stopped => {
+                    break;
+                },
+                msg = receiver.recv() => {
+                    match msg {
+                        Ok((peer, message_bytes)) => {
+                            // NOTE: malformed messages are ignored, just like the messages of unknown peers.
+                            let message = match Message::try_from(message_bytes) {
+                                Ok(message) => message,
+                                Err(e) => {
+                                    self.log(LogType::Debug, &format!("ignoring lobby message of {}: {}", peer, e)).await;
+                                    continue;
+                                }
+                            };
+                            if let Err(e) = self.handle_message(&mut sender, peer, message).await {
+                                self.log(LogType::Error, &format!("failed to handle lobby message: {}", e)).await;
+                            }
+                        },
+                        Err(_) => {
+                            self.log(LogType::Error, "failed to receive lobby message").await;
+                            break;
+                        },
+                    }
+                },
+                command = commands.next() => {
+                    if let Some(command) = command
+                        && let Err(e) = self.handle_command(&mut sender, command).await {
+                            self.log(LogType::Error, &format!("failed to handle lobby command: {}", e)).await;
+                        }
+                },
+                opponent = finished.next() => {
+                    if let Some(opponent) = opponent {
+                        self.game_over(&opponent).await;
+                    }
+                },
+                _ = clock.sleep_until(next_presence) => {
+                    next_presence = clock.current() + PRESENCE_INTERVAL;
+
+                    if let Err(e) = self.announce(&mut sender).await {
+                        self.log(LogType::Error, &format!("failed to announce presence: {}", e)).await;
+                    }
+                }
+            }
+
+            self.publish_status().await;
+        }
+    }
+
+    /// Announces the node's presence and challenges the configured peer, once it's online.
+    async fn announce(&mut self, sender: &mut impl Sender<PublicKey = P>) -> eyre::Result<()> {
+        send(sender, Recipients::All, Message::Presence).await?;
+
+        let Some(index) = self
+            .auto_challenge
+            .as_ref()
+            .and_then(|public_key| self.index(public_key))
+        else {
+            return Ok(());
+        };
+
+        let peer = &self.peers[index];
+        if !peer.playing && !self.busy() && peer.challenge.is_none() && self.online(index) {
+            self.challenge(sender, index).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles a message of the given peer.
+    ///
+    /// Messages of unknown peers are ignored, since they can't be challenged.
+    async fn handle_message(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        public_key: P,
+        message: Message,
+    ) -> eyre::Result<()> {
+        let Some(index) = self.index(&public_key) else {
+            return Ok(());
+        };
+        self.peers[index].last_seen = Some(self.context.as_present().current());
+
+        match message {
+            Message::Presence => (),
+            Message::Challenge => {
+                if self.peers[index].playing || self.busy() {
+                    // NOTE: only a single game is played against every peer, or against
+                    // a single opponent at all if configured by the policy.
+                    return send(sender, Recipients::One(public_key), Message::Decline).await;
+                }
+
+                match self.peers[index].challenge {
+                    // Both players challenged each other at the same time, so the challenge is accepted.
+                    Some(Side::Own) => self.accept(sender, index).await?,
+                    _ => {
+                        self.peers[index].challenge = Some(Side::Opponent);
+                        self.log(
+                            LogType::Info,
+                            &format!("⚔️ {} challenged you; press a to accept", public_key),
+                        )
+                        .await;
+
+                        if self.accept_challenges {
+                            self.accept(sender, index).await?;
+                        }
+                    }
+                }
+            }
+            Message::Accept => {
+                // Both players accepted each other's challenge at the same time.
+                if self.peers[index].playing {
+                    return Ok(());
+                }
+                if self.busy() {
+                    return Err(eyre::eyre!(
+                        "{} accepted the challenge while already playing",
+                        public_key
+                    ));
+                }
+                if self.peers[index].challenge != Some(Side::Own) {
+                    return Err(eyre::eyre!(
+                        "{} accepted a challenge that was not sent",
+                        public_key
+                    ));
+                }
+
+                self.matched(sender, index).await?;
+            }
+            Message::Decline => {
+                if self.peers[index].challenge.take().is_some() {
+                    self.log(
+                        LogType::Info,
+                        &format!("{} declined the challenge", public_key),
+                    )
+                    .await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles a command that was sent to the actor's mailbox.
+    async fn handle_command(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        command: Command,
+    ) -> eyre::Result<()> {
+        let index = match command {
+            Command::Challenge(index) | Command::Accept(index) | Command::Decline(index) => index,
+        };
+        if index >= self.peers.len() {
+            return Err(eyre::eyre!("unknown peer: {}", index));
+        }
+        if self.peers[index].playing {
+            return Err(eyre::eyre!("already playing against peer"));
+        }
+        if self.busy() && !matches!(command, Command::Decline(_)) {
+            return Err(eyre::eyre!("already playing against an opponent"));
+        }
+
+        match command {
+            Command::Challenge(_) => match self.peers[index].challenge {
+                Some(Side::Own) => Err(eyre::eyre!("peer was already challenged")),
+                // The peer already challenged us, so the challenge is accepted instead.
+                Some(Side::Opponent) => self.accept(sender, index).await,
+                None => self.challenge(sender, index).await,
+            },
+            Command::Accept(_) => {
+                if self.peers[index].challenge != Some(Side::Opponent) {
+                    return Err(eyre::eyre!("peer did not send a challenge"));
+                }
+
+                self.accept(sender, index).await
+            }
+            Command::Decline(_) => {
+                if self.peers[index].challenge != Some(Side::Opponent) {
+                    return Err(eyre::eyre!("peer did not send a challenge"));
+                }
+
+                self.decline(sender, index).await
+            }
+        }
+    }
+
+    /// Challenges the given peer to a game.
+    async fn challenge(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        index: usize,
+    ) -> eyre::Result<()> {
+        let public_key = self.peers[index].public_key.clone();
+        if !self.online(index) {
+            self.log(
+                LogType::Info,
+                &format!("{} is offline; the challenge might be lost", public_key),
+            )
+            .await;
+        }
+
+        send(
+            sender,
+            Recipients::One(public_key.clone()),
+            Message::Challenge,
+        )
+        .await?;
+        self.peers[index].challenge = Some(Side::Own);
+        self.log(
+            LogType::Info,
+            &format!("⚔️ challenged {}; waiting for the answer", public_key),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Accepts the challenge of the given peer, which starts the game.
+    async fn accept(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        index: usize,
+    ) -> eyre::Result<()> {
+        let public_key = self.peers[index].public_key.clone();
+        send(sender, Recipients::One(public_key), Message::Accept).await?;
+        self.matched(sender, index).await
+    }
+
+    /// Declines the challenge of the given peer, or withdraws the own challenge.
+    async fn decline(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        index: usize,
+    ) -> eyre::Result<()> {
+        let public_key = self.peers[index].public_key.clone();
+        send(sender, Recipients::One(public_key), Message::Decline).await?;
+        self.peers[index].challenge = None;
+
+        Ok(())
+    }
+
+    /// Marks the given peer as playing and hands it over to start the game.
+    ///
+    /// If only a single opponent is played against, the pending challenges of all other
+    /// peers are declined.
+    async fn matched(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        index: usize,
+    ) -> eyre::Result<()> {
+        let public_key = self.peers[index].public_key.clone();
+        self.peers[index].challenge = None;
+        self.peers[index].playing = true;
+
+        // NOTE: the receiver is only dropped if the node is shutting down.
+        let _ = self.matched.send(public_key.clone()).await;
+
+        self.log(
+            LogType::Info,
+            &format!("🤝 starting game against {}", public_key),
+        )
+        .await;
+
+        if !self.policy.single_opponent() {
+            return Ok(());
+        }
+        for other in 0..self.peers.len() {
+            if self.peers[other].challenge.is_some() {
+                self.decline(sender, other).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Frees the given opponent once its game is over, so that new games can be started again.
+    async fn game_over(&mut self, public_key: &P) {
+        let Some(index) = self.index(public_key) else {
+            return;
+        };
+        if !self.peers[index].playing {
+            return;
+        }
+
+        self.peers[index].playing = false;
+        self.log(
+            LogType::Info,
+            &format!(
+                "🏁 game against {} is over; challenges are accepted again",
+                public_key
+            ),
+        )
+        .await;
+    }
+
+    /// Checks if no further games are started, since the single opponent is still played against.
+    fn busy(&self) -> bool {
+        self.policy.single_opponent() && self.peers.iter().any(|peer| peer.playing)
+    }
+
+    /// Returns the index of the known peer with the given public key.
+    fn index(&self, public_key: &P) -> Option<usize> {
+        self.peers
+            .iter()
+            .position(|peer| peer.public_key == *public_key)
+    }
+
+    /// Checks if the given peer announced its presence recently.
+    fn online(&self, index: usize) -> bool {
+        let now = self.context.as_present().current();
+
+        self.peers[index].last_seen.is_some_and(|last_seen| {
+            now.duration_since(last_seen).unwrap_or_default() < ONLINE_TIMEOUT
+        })
+    }
+
+    /// Publishes the status of the lobby, if it changed since it was last published.
+    async fn publish_status(&mut self) {
+        let peers = (0..self.peers.len())
+            .map(|index| PeerStatus {
+                public_key: self.peers[index].public_key.to_string(),
+                online: self.online(index),
+                challenge: self.peers[index].challenge,
+                playing: self.peers[index].playing,
+            })
+            .collect::<Vec<PeerStatus>>();
+        if peers == self.published {
+            return;
+        }
+
+        self.published = peers.clone();
+        self.events.publish(Event::Lobby { peers }).await;
+    }
+
+    async fn log(&mut self, log_type: LogType, content: &str) {
+        self.events
+            .publish(Event::Log {
+                log: Log::new(log_type, content.into()),
+            })
+            .await;
+    }
+}
+
+/// Sends the given message to the recipients.
+async fn send<P: PublicKey>(
+    sender: &mut impl Sender<PublicKey = P>,
+    recipients: Recipients<P>,
+    message: Message,
+) -> eyre::Result<()> {
+    sender
+        .send(recipients, message.into(), false)
+        .await
+        .wrap_err("failed to send lobby message")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use commonware_cryptography::{PrivateKeyExt as _, Signer, ed25519};
+    use commonware_p2p::simulated::{self, Link, Network};
+    use commonware_runtime::{Metrics, Runner, deterministic};
+
+    /// Runs the lobbies of three known peers, where every lobby is configured with the given
+    /// auto-accept flag and peer to challenge (as index), and returns the matched opponents.
+    fn run_lobbies(config: [(bool, Option<usize>); 3]) -> [Vec<ed25519::PublicKey>; 3] {
+        run_lobbies_with_policies(config, Default::default())
+    }
+
+    /// Runs the lobbies like [`run_lobbies`], where every lobby uses the given policy.
+    fn run_lobbies_with_policies(
+        config: [(bool, Option<usize>); 3],
+        policies: [Policy<ed25519::PublicKey>; 3],
+    ) -> [Vec<ed25519::PublicKey>; 3] {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+
+            let public_keys = (0..3)
+                .map(|seed| ed25519::PrivateKey::from_seed(seed).public_key())
+                .collect::<Vec<_>>();
+            for from in &public_keys {
+                for to in public_keys.iter().filter(|to| *to != from) {
+                    oracle
+                        .add_link(
+                            from.clone(),
+                            to.clone(),
+                            Link {
+                                latency: Duration::from_millis(10),
+                                jitter: Duration::ZERO,
+                                success_rate: 1.0,
+                            },
+                        )
+                        .await
+                        .unwrap();
+                }
+            }
+
+            let mut matches = Vec::new();
+            for (id, ((accept_challenges, challenge), policy)) in
+                config.into_iter().zip(policies).enumerate()
+            {
+                let (sender, receiver) = oracle
+                    .control(public_keys[id].clone())
+                    .register(0)
+                    .await
+                    .unwrap();
+                let peers = public_keys
+                    .iter()
+                    .filter(|peer| **peer != public_keys[id])
+                    .cloned()
+                    .collect();
+
+                let (actor, _, matched) = LobbyActor::new(
+                    context.with_label(&format!("lobby_{}", id)),
+                    EventSink::default(),
+                    peers,
+                    policy,
+                    accept_challenges,
+                    challenge.map(|peer| public_keys[peer].clone()),
+                );
+                actor.start(sender, receiver);
+                matches.push(matched);
+            }
+
+            // The lobbies without a match are still waiting after all challenges were answered.
+            context.sleep(Duration::from_secs(60)).await;
+            let mut opponents = [Vec::new(), Vec::new(), Vec::new()];
+            for (opponents, mut matched) in opponents.iter_mut().zip(matches) {
+                while let Ok(Some(opponent)) = matched.try_next() {
+                    opponents.push(opponent);
+                }
+            }
+
+            opponents
+        })
+    }
+
+    fn public_key(seed: u64) -> ed25519::PublicKey {
+        ed25519::PrivateKey::from_seed(seed).public_key()
+    }
+
+    #[test]
+    fn test_challenge() {
+        // the first peer challenges the second peer, which accepts the challenge
+        let opponents = run_lobbies([(false, Some(1)), (true, None), (false, None)]);
+        assert_eq!(
+            [vec![public_key(1)], vec![public_key(0)], vec![]],
+            opponents
+        );
+
+        // challenges that are not accepted don't start a game
+        let opponents = run_lobbies([(false, Some(2)), (false, None), (false, None)]);
+        assert_eq!([vec![], vec![], vec![]], opponents);
+    }
+
+    #[test]
+    fn test_simultaneous_challenge() {
+        // both peers challenged each other, so both challenges are accepted
+        let opponents = run_lobbies([(false, Some(1)), (false, Some(0)), (false, None)]);
+        assert_eq!(
+            [vec![public_key(1)], vec![public_key(0)], vec![]],
+            opponents
+        );
+    }
+
+    #[test]
+    fn test_multiple_games() {
+        // the second peer accepts the challenges of both other peers, so it plays two games
+        let [first, second, third] =
+            run_lobbies([(false, Some(1)), (true, None), (false, Some(1))]);
+        assert_eq!(vec![public_key(1)], first);
+        assert_eq!(vec![public_key(1)], third);
+        assert_eq!(2, second.len());
+        assert!(second.contains(&public_key(0)) && second.contains(&public_key(2)));
+    }
+
+    #[test]
+    fn test_blocklist() {
+        // the second peer blocks the first peer, so its challenge is never answered
+        let blocked = Policy::new(None, vec![public_key(0)], false);
+        let opponents = run_lobbies_with_policies(
+            [(false, Some(1)), (true, None), (false, None)],
+            [Policy::default(), blocked, Policy::default()],
+        );
+        assert_eq!([vec![], vec![], vec![]], opponents);
+
+        // only the allowlisted third peer is accepted
+        let allowed = Policy::new(Some(vec![public_key(2)]), Vec::new(), false);
+        let opponents = run_lobbies_with_policies(
+            [(false, Some(1)), (true, None), (false, Some(1))],
+            [Policy::default(), allowed, Policy::default()],
+        );
+        assert_eq!(
+            [vec![], vec![public_key(2)], vec![public_key(1)]],
+            opponents
+        );
+    }
+
+    #[test]
+    fn test_malformed_message() {
+        // the lobby ignores the malformed message and still accepts the following challenge
+        let opponent = deterministic::Runner::seeded(0).start(|context| async move {
+            let (network, mut oracle) = Network::new(
+                context.with_label("network"),
+                simulated::Config {
+                    max_size: 1024,
+                    disconnect_on_block: true,
+                    tracked_peer_sets: None,
+                },
+            );
+            network.start();
+
+            for (from, to) in [(0, 1), (1, 0)] {
+                oracle
+                    .add_link(
+                        public_key(from),
+                        public_key(to),
+                        Link {
+                            latency: Duration::from_millis(10),
+                            jitter: Duration::ZERO,
+                            success_rate: 1.0,
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            let (mut sender, _) = oracle.control(public_key(0)).register(0).await.unwrap();
+            let (lobby_sender, lobby_receiver) =
+                oracle.control(public_key(1)).register(0).await.unwrap();
+            let (actor, _, mut matched) = LobbyActor::new(
+                context.with_label("lobby"),
+                EventSink::default(),
+                vec![public_key(0)],
+                Policy::default(),
+                true,
+                None,
+            );
+            actor.start(lobby_sender, lobby_receiver);
+
+            let recipients = Recipients::One(public_key(1));
+            sender
+                .send(
+                    recipients.clone(),
+                    Bytes::from_static(b"\x00invalid"),
+                    false,
+                )
+                .await
+                .unwrap();
+            sender
+                .send(recipients, Message::Challenge.into(), false)
+                .await
+                .unwrap();
+
+            matched.next().await
+        });
+        assert_eq!(Some(public_key(0)), opponent);
+    }
+
+    #[test]
+    fn test_game_over() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let single = Policy::new(None, Vec::new(), true);
+            let (mut actor, _, _) = LobbyActor::new(
+                context.with_label("lobby"),
+                EventSink::default(),
+                vec![public_key(1), public_key(2)],
+                single,
+                false,
+                None,
+            );
+
+            // the single opponent keeps the lobby busy until its game is over
+            actor.peers[0].playing = true;
+            assert!(actor.busy());
+            actor.game_over(&public_key(2)).await;
+            assert!(actor.busy());
+            actor.game_over(&public_key(1)).await;
+            assert!(!actor.busy() && !actor.peers[0].playing);
+        });
+    }
+
+    #[test]
+    fn test_single_opponent() {
+        // the second peer only plays against a single opponent, so one of the challenges is declined
+        let single = Policy::new(None, Vec::new(), true);
+        let [first, second, third] = run_lobbies_with_policies(
+            [(false, Some(1)), (true, None), (false, Some(1))],
+            [Policy::default(), single, Policy::default()],
+        );
+        assert_eq!(1, second.len());
+        assert_eq!(1, first.len() + third.len());
+        assert_eq!(
+            second[0],
+            if first.is_empty() {
+                public_key(2)
+            } else {
+                public_key(0)
+            }
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use futures::channel::mpsc::{Sender, TrySendError};
+use serde::{Deserialize, Serialize};
+
+use crate::events::Side;
+
+/// The commands that can be sent to the lobby actor, e.g. by the GUI.
+///
+/// The peers are referenced by their index in the list of known peers.
+pub enum Command {
+    /// Challenges the peer to a game.
+    Challenge(usize),
+    /// Accepts the challenge of the peer.
+    Accept(usize),
+    /// Declines the challenge of the peer.
+    Decline(usize),
+}
+
+/// The mailbox to send commands to the lobby actor.
+///
+/// NOTE: the commands are sent without waiting, since the lobby actor might be blocked
+/// on publishing its status to the sender of the command (e.g. the GUI).
+#[derive(Clone)]
+pub struct Mailbox {
+    sender: Sender<Command>,
+}
+
+impl Mailbox {
+    pub fn new(sender: Sender<Command>) -> Self {
+        Self { sender }
+    }
+
+    pub fn challenge(&mut self, peer: usize) -> Result<(), TrySendError<Command>> {
+        self.sender.try_send(Command::Challenge(peer))
+    }
+
+    pub fn accept(&mut self, peer: usize) -> Result<(), TrySendError<Command>> {
+        self.sender.try_send(Command::Accept(peer))
+    }
+
+    pub fn decline(&mut self, peer: usize) -> Result<(), TrySendError<Command>> {
+        self.sender.try_send(Command::Decline(peer))
+    }
+}
+
+/// The status of a known peer, as shown in the lobby.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PeerStatus {
+    pub public_key: String,
+    /// Signals if the peer announced its presence recently.
+    pub online: bool,
+    /// The player that sent the pending challenge, if any.
+    pub challenge: Option<Side>,
+    /// Signals if a game against the peer is running.
+    pub playing: bool,
+}
+
+/// Message describes the messages exchanged between the nodes in the lobby.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum Message {
+    /// Announces that the node is online.
+    Presence,
+    /// Challenges the receiving peer to a game.
+    Challenge,
+    /// Accepts the challenge of the receiving peer.
+    Accept,
+    /// Declines the challenge of the receiving peer, or withdraws the own challenge.
+    Decline,
+}
+
+impl From<Message> for bytes::Bytes {
+    fn from(val: Message) -> Self {
+        let serialized = serde_yaml::to_string(&val).expect("failed to serialize message");
+
+        bytes::Bytes::from(serialized.into_bytes())
+    }
+}
+
+/// Decodes a received lobby message.
+///
+/// NOTE: every authorized peer can send lobby messages, so malformed messages must not panic.
+impl TryFrom<bytes::Bytes> for Message {
+    type Error = eyre::Report;
+
+    fn try_from(value: bytes::Bytes) -> eyre::Result<Self> {
+        serde_yaml::from_slice(&value)
+            .map_err(|e| eyre::eyre!("failed to decode lobby message: {}", e))
+    }
+}
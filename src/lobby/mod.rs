@@ -0,0 +1,17 @@
+//! The lobby to find an opponent among the known peers.
+//!
+//! All nodes periodically announce their presence on a dedicated channel of the p2p network,
+//! so that the online status of the known peers can be shown. A player can challenge any
+//! of the peers, which is either accepted or declined by the challenged peer.
+//! Every accepted challenge starts a new game with the challenging peer, so that
+//! several games can be played at the same time.
+//!
+//! Which peers are accepted as opponents is configured with a [`Policy`], e.g. to block peers
+//! or to only play against a single opponent.
+mod actor;
+mod ingress;
+mod policy;
+
+pub use actor::LobbyActor;
+pub use ingress::{Mailbox, PeerStatus};
+pub use policy::Policy;
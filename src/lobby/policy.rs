@@ -0,0 +1,76 @@
+use commonware_cryptography::PublicKey;
+
+/// The policy which of the known peers are accepted as opponents.
+#[derive(Clone, Debug)]
+pub struct Policy<P: PublicKey> {
+    /// The peers that are allowed as opponents, or all peers if not set.
+    allowlist: Option<Vec<P>>,
+    /// The peers that are never accepted as opponents, even if they are allowlisted.
+    blocklist: Vec<P>,
+    /// Signals if only a single opponent is played against at a time, while all other peers are declined.
+    single_opponent: bool,
+}
+
+impl<P: PublicKey> Default for Policy<P> {
+    fn default() -> Self {
+        Self {
+            allowlist: None,
+            blocklist: Vec::new(),
+            single_opponent: false,
+        }
+    }
+}
+
+impl<P: PublicKey> Policy<P> {
+    pub fn new(allowlist: Option<Vec<P>>, blocklist: Vec<P>, single_opponent: bool) -> Self {
+        Self {
+            allowlist,
+            blocklist,
+            single_opponent,
+        }
+    }
+
+    /// Checks if the given peer is accepted as an opponent.
+    pub fn allows(&self, peer: &P) -> bool {
+        !self.blocklist.contains(peer)
+            && self
+                .allowlist
+                .as_ref()
+                .is_none_or(|allowlist| allowlist.contains(peer))
+    }
+
+    /// Checks if only a single opponent is played against.
+    pub fn single_opponent(&self) -> bool {
+        self.single_opponent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commonware_cryptography::{PrivateKeyExt as _, Signer, ed25519};
+
+    #[test]
+    fn test_allows() {
+        let [first, second, third] =
+            [0, 1, 2].map(|seed| ed25519::PrivateKey::from_seed(seed).public_key());
+
+        // all peers are allowed by default
+        let policy = Policy::default();
+        assert!(policy.allows(&first) && policy.allows(&second));
+        assert!(!policy.single_opponent());
+
+        let policy = Policy::new(None, vec![second.clone()], true);
+        assert!(policy.allows(&first) && !policy.allows(&second));
+
+        // the blocklist takes precedence over the allowlist
+        let policy = Policy::new(
+            Some(vec![first.clone(), second.clone()]),
+            vec![second.clone()],
+            false,
+        );
+        assert!(policy.allows(&first));
+        assert!(!policy.allows(&second) && !policy.allows(&third));
+    }
+}
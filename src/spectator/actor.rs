@@ -0,0 +1,354 @@
+use std::{collections::HashSet, time::Duration};
+
+use commonware_cryptography::PublicKey;
+use commonware_macros::select;
+use commonware_p2p::{Receiver, Recipients, Sender};
+use commonware_runtime::{Clock, ContextCell, Spawner, spawn_cell};
+use futures::channel::mpsc;
+
+use crate::{
+    events::{Event, EventSink, Side},
+    game::{Cell, Coordinate, GRID_SIZE},
+    gui::{self, Board, GridView, Log, LogType},
+};
+
+use super::ingress::{Message, Update};
+
+/// The interval at which the spectator renews its subscriptions.
+const SUBSCRIBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A watched game between two players, as reconstructed from the received updates.
+///
+/// Both players of a game may broadcast its updates, so every update is applied idempotently.
+struct Watched {
+    /// The public keys of both players, where the first player's board is shown as the own grid.
+    players: [String; 2],
+    /// The boards of both players, in the order of the players.
+    grids: [GridView; 2],
+    /// The logged results, identified by the attacking player and the number of the move.
+    logged: HashSet<(usize, u16)>,
+    /// Signals if the start of the current game of the series was received.
+    started: bool,
+    /// Signals if the end of the current game was received, so that the next start resets the boards.
+    ended: bool,
+    /// The events are published to the game's board.
+    events: EventSink,
+}
+
+impl Watched {
+    fn new(players: [String; 2], events: EventSink) -> Self {
+        Self {
+            players,
+            grids: [empty_grid(), empty_grid()],
+            logged: HashSet::new(),
+            started: false,
+            ended: false,
+            events,
+        }
+    }
+}
+
+/// The spectator actor subscribes to the games of the given players and renders
+/// them read-only on a board per game.
+pub struct SpectatorActor<R: Spawner + Clock, P: PublicKey> {
+    context: ContextCell<R>,
+
+    /// The players to watch.
+    players: Vec<P>,
+
+    /// The GUI, which shows a board for every watched game.
+    gui: gui::Mailbox,
+
+    /// The watched games, in the order they were first seen.
+    games: Vec<Watched>,
+}
+
+impl<R: Spawner + Clock, P: PublicKey> SpectatorActor<R, P> {
+    pub fn new(context: R, players: Vec<P>, gui: gui::Mailbox) -> Self {
+        Self {
+            context: ContextCell::new(context),
+            players,
+            gui,
+            games: Vec::new(),
+        }
+    }
+
+    pub fn start(
+        mut self,
+        sender: impl Sender<PublicKey = P>,
+        receiver: impl Receiver<PublicKey = P>,
+    ) {
+        spawn_cell!(self.context, self.run(sender, receiver).await);
+    }
+
+    async fn run(
+        mut self,
+        mut sender: impl Sender<PublicKey = P>,
+        mut receiver: impl Receiver<PublicKey = P>,
+    ) {
+        let mut stopped = self.context.as_present().stopped();
+
+        // NOTE: the subscriptions are renewed at fixed times, so that they are not
+        // delayed indefinitely by the received updates.
+        let clock = self.context.as_present().clone();
+        let mut next_subscription = clock.current();
+
+        loop {
+            select! {
+                _ = &mut stopped => {
+                    break;
+                },
+                msg = receiver.recv() => {
+                    let Ok((peer, message_bytes)) = msg else {
+                        break;
+                    };
+
+                    // NOTE: the messages of other peers than the players and malformed messages are dropped.
+                    if self.players.contains(&peer)
+                        && let Ok(Message::Update { opponent, update }) = Message::try_from(message_bytes)
+                    {
+                        self.handle_update(peer.to_string(), opponent, update).await;
+                    }
+                },
+                _ = clock.sleep_until(next_subscription) => {
+                    next_subscription = clock.current() + SUBSCRIBE_INTERVAL;
+
+                    // NOTE: the subscription is lost if the player is not connected yet,
+                    // which is why it's renewed periodically.
+                    let recipients = Recipients::Some(self.players.clone());
+                    let _ = sender.send(recipients, Message::Subscribe.into(), false).await;
+                },
+            }
+        }
+    }
+
+    /// Applies the update of the given player's game against the given opponent.
+    async fn handle_update(&mut self, player: String, opponent: String, update: Update) {
+        let index = self.game(&player, &opponent).await;
+        let game = &mut self.games[index];
+
+        // The index of the board of the player that published the update.
+        let own = match game.players[0] == player {
+            true => 0,
+            false => 1,
+        };
+        let attacker = |by: Side| match by {
+            Side::Own => own,
+            Side::Opponent => 1 - own,
+        };
+
+        let mut logs = Vec::new();
+        match update {
+            Update::Start { first } => {
+                if game.started && !game.ended {
+                    return;
+                }
+
+                (game.started, game.ended) = (true, false);
+                game.grids = [empty_grid(), empty_grid()];
+                game.logged.clear();
+                logs.push(Log::new(
+                    LogType::Info,
+                    format!(
+                        "🎮 {} has the first turn",
+                        short_key(&game.players[attacker(first)])
+                    ),
+                ));
+            }
+            Update::Attack { by, x, y, .. } => {
+                if !valid(x, y) {
+                    return;
+                }
+
+                game.grids[1 - attacker(by)].last_move = Some((x, y));
+            }
+            Update::Result {
+                by,
+                number,
+                x,
+                y,
+                hit,
+            } => {
+                if !valid(x, y) {
+                    return;
+                }
+
+                let attacker = attacker(by);
+                let grid = &mut game.grids[1 - attacker];
+                grid.cells[y as usize - 1][x as usize - 1] =
+                    if hit { Cell::Hit } else { Cell::Miss };
+                grid.last_move = Some((x, y));
+
+                if game.logged.insert((attacker, number)) {
+                    let log_type = match (attacker, hit) {
+                        (0, true) => LogType::Hit,
+                        (0, false) => LogType::Miss,
+                        (_, true) => LogType::OpponentHit,
+                        (_, false) => LogType::OpponentMiss,
+                    };
+                    let result = if hit { "hit" } else { "miss" };
+                    logs.push(Log::new(
+                        log_type,
+                        format!("{}: {}", Coordinate::new(x, y, hit), result),
+                    ));
+                }
+            }
+            Update::End { winner } => {
+                if game.ended {
+                    return;
+                }
+
+                game.ended = true;
+                let content = match winner {
+                    Some(winner) => format!(
+                        "👑 {} won the game",
+                        short_key(&game.players[attacker(winner)])
+                    ),
+                    None => "the game ended without a winner".to_string(),
+                };
+                logs.push(Log::new(LogType::Won, content));
+            }
+            // Both players revealed their ships, so the full boards are shown.
+            Update::GameOver {
+                own_board,
+                opponent_board,
+                ..
+            } => {
+                game.ended = true;
+                game.grids[own] = GridView::new(own_board, None);
+                game.grids[1 - own] = GridView::new(opponent_board, None);
+            }
+        }
+
+        game.events
+            .publish(Event::Grids {
+                own: game.grids[0].clone(),
+                opponent: game.grids[1].clone(),
+            })
+            .await;
+        for log in logs {
+            game.events.publish(Event::Log { log }).await;
+        }
+    }
+
+    /// Returns the index of the game between the given players, adding its board if it's new.
+    async fn game(&mut self, player: &str, opponent: &str) -> usize {
+        let players = [player.to_string(), opponent.to_string()];
+        if let Some(index) = self
+            .games
+            .iter()
+            .position(|game| players.iter().all(|player| game.players.contains(player)))
+        {
+            return index;
+        }
+
+        let (sender, receiver) = mpsc::channel(16);
+        let title = format!("{} vs {}", short_key(player), short_key(opponent));
+
+        // NOTE: the spectator can't send any commands to the games.
+        let _ = self
+            .gui
+            .add_board(Board {
+                title,
+                events: receiver,
+                mailbox: None,
+            })
+            .await;
+
+        self.games
+            .push(Watched::new(players, EventSink::new(vec![sender])));
+        self.games.len() - 1
+    }
+}
+
+/// Returns a grid without any attacks.
+fn empty_grid() -> GridView {
+    GridView::new(
+        vec![vec![Cell::Empty; GRID_SIZE as usize]; GRID_SIZE as usize],
+        None,
+    )
+}
+
+/// Checks if the given coordinate is on the grid.
+fn valid(x: u8, y: u8) -> bool {
+    (1..=GRID_SIZE).contains(&x) && (1..=GRID_SIZE).contains(&y)
+}
+
+/// Shortens the given public key to fit into the title of a board.
+fn short_key(public_key: &str) -> String {
+    public_key.chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commonware_cryptography::ed25519;
+    use commonware_runtime::{Runner, deterministic};
+
+    #[test]
+    fn test_updates_of_both_players() {
+        deterministic::Runner::seeded(0).start(|context| async move {
+            let (boards_sender, mut boards) = mpsc::channel(1);
+            let mut actor = SpectatorActor::<_, ed25519::PublicKey>::new(
+                context,
+                Vec::new(),
+                gui::Mailbox::new(boards_sender),
+            );
+            let (a, b) = ("a".to_string(), "b".to_string());
+
+            // both players publish the start and the results of their moves
+            actor
+                .handle_update(a.clone(), b.clone(), Update::Start { first: Side::Own })
+                .await;
+            actor
+                .handle_update(
+                    b.clone(),
+                    a.clone(),
+                    Update::Start {
+                        first: Side::Opponent,
+                    },
+                )
+                .await;
+            let result = |by, number, x, y, hit| Update::Result {
+                by,
+                number,
+                x,
+                y,
+                hit,
+            };
+            actor
+                .handle_update(a.clone(), b.clone(), result(Side::Own, 1, 1, 1, true))
+                .await;
+            actor
+                .handle_update(b.clone(), a.clone(), result(Side::Opponent, 1, 1, 1, true))
+                .await;
+            actor
+                .handle_update(b.clone(), a.clone(), result(Side::Own, 2, 2, 2, false))
+                .await;
+
+            // the updates of both players are shown on the same board
+            assert_eq!(1, actor.games.len());
+            let board = boards.try_next().unwrap().unwrap();
+            assert_eq!("a vs b", board.title);
+            assert!(board.mailbox.is_none());
+
+            let grids = &actor.games[0].grids;
+            assert_eq!(Cell::Miss, grids[0].cells[1][1]);
+            assert_eq!(Cell::Hit, grids[1].cells[0][0]);
+
+            // the duplicate updates are only logged once
+            let mut events = board.events;
+            let mut logs = Vec::new();
+            while let Ok(Some(event)) = events.try_next() {
+                if let Event::Log { log } = event {
+                    logs.push(log.log_type());
+                }
+            }
+            assert_eq!(
+                vec![LogType::Info, LogType::Hit, LogType::OpponentMiss],
+                logs
+            );
+        });
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::{Duration, SystemTime};
+
+use commonware_cryptography::PublicKey;
+use commonware_macros::select;
+use commonware_p2p::{Receiver, Recipients, Sender};
+use commonware_runtime::{Clock, ContextCell, Spawner, spawn_cell};
+use futures::{
+    StreamExt,
+    channel::mpsc,
+    stream::{self, BoxStream},
+};
+
+use crate::events::Event;
+
+use super::ingress::{Game, Mailbox, Message, Update};
+
+/// The duration after which a subscription expires, if the spectator did not renew it.
+pub const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The broadcast actor sends the updates of the player's games to the subscribed spectators.
+///
+/// Only the events that are converted to an [`Update`] are broadcast, so that the spectators
+/// never receive the positions of the ships before the game is over.
+pub struct BroadcastActor<R: Spawner + Clock, P: PublicKey> {
+    context: ContextCell<R>,
+
+    /// The spectators that are allowed to subscribe.
+    allowed: Vec<P>,
+
+    /// The subscribed spectators, with the time of their last subscription.
+    subscribers: Vec<(P, SystemTime)>,
+
+    /// The games that are added e.g. by the game manager.
+    ///
+    /// NOTE: this is taken out of the actor once it's running, so that it can be polled
+    /// while broadcasting the updates.
+    games: Option<mpsc::Receiver<Game>>,
+}
+
+impl<R: Spawner + Clock, P: PublicKey> BroadcastActor<R, P> {
+    /// Creates a new broadcast actor for the given allowed spectators.
+    ///
+    /// Returns the mailbox to add the games to broadcast.
+    pub fn new(context: R, allowed: Vec<P>) -> (Self, Mailbox) {
+        let (sender, receiver) = mpsc::channel(1);
+
+        let actor = Self {
+            context: ContextCell::new(context),
+            allowed,
+            subscribers: Vec::new(),
+            games: Some(receiver),
+        };
+
+        (actor, Mailbox::new(sender))
+    }
+
+    pub fn start(
+        mut self,
+        sender: impl Sender<PublicKey = P>,
+        receiver: impl Receiver<PublicKey = P>,
+    ) {
+        spawn_cell!(self.context, self.run(sender, receiver).await);
+    }
+
+    async fn run(
+        mut self,
+        mut sender: impl Sender<PublicKey = P>,
+        mut receiver: impl Receiver<PublicKey = P>,
+    ) {
+        // NOTE: the games are chained with a pending stream, so that the loop is not woken up
+        // repeatedly once the mailbox is dropped.
+        let mut games = self
+            .games
+            .take()
+            .expect("actor must only be run once")
+            .chain(stream::pending());
+
+        // The events of all games are merged into a single stream, tagged with their opponent.
+        // NOTE: the pending stream keeps the merged stream from ending while there are no games.
+        let mut events = stream::select_all([stream::pending().boxed()]);
+
+        let mut stopped = self.context.as_present().stopped();
+
+        loop {
+            select! {
+                _ = &mut stopped => {
+                    break;
+                },
+                msg = receiver.recv() => {
+                    let Ok((peer, message_bytes)) = msg else {
+                        break;
+                    };
+                    // NOTE: malformed messages are dropped, like the subscriptions of peers that are not allowed.
+                    if let Ok(message) = Message::try_from(message_bytes) {
+                        self.handle_message(peer, message);
+                    }
+                },
+                game = games.next() => {
+                    if let Some(game) = game {
+                        events.push(game_events(game));
+                    }
+                },
+                event = events.next() => {
+                    if let Some((opponent, event)) = event
+                        && let Some(update) = Update::from_event(&event)
+                    {
+                        self.broadcast(&mut sender, opponent, update).await;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Handles a message of the given peer.
+    ///
+    /// The subscriptions of spectators that are not allowed are ignored.
+    fn handle_message(&mut self, public_key: P, message: Message) {
+        if !matches!(message, Message::Subscribe) || !self.allowed.contains(&public_key) {
+            return;
+        }
+
+        let now = self.context.as_present().current();
+        match self
+            .subscribers
+            .iter_mut()
+            .find(|(subscriber, _)| *subscriber == public_key)
+        {
+            Some((_, subscribed)) => *subscribed = now,
+            None => self.subscribers.push((public_key, now)),
+        }
+    }
+
+    /// Sends the update of the game against the given opponent to all subscribed spectators.
+    async fn broadcast(
+        &mut self,
+        sender: &mut impl Sender<PublicKey = P>,
+        opponent: String,
+        update: Update,
+    ) {
+        let now = self.context.as_present().current();
+        self.subscribers.retain(|(_, subscribed)| {
+            now.duration_since(*subscribed).unwrap_or_default() < SUBSCRIPTION_TIMEOUT
+        });
+        if self.subscribers.is_empty() {
+            return;
+        }
+
+        let recipients = self
+            .subscribers
+            .iter()
+            .map(|(subscriber, _)| subscriber.clone())
+            .collect();
+
+        // NOTE: spectating is best effort, so failing to send an update does not affect the game.
+        let _ = sender
+            .send(
+                Recipients::Some(recipients),
+                Message::Update { opponent, update }.into(),
+                false,
+            )
+            .await;
+    }
+}
+
+/// Tags the events of the given game with the public key of its opponent.
+fn game_events(game: Game) -> BoxStream<'static, (String, Event)> {
+    let opponent = game.opponent;
+    game.events
+        .map(move |event| (opponent.clone(), event))
+        .boxed()
+}
@@ -0,0 +1,228 @@
+use futures::{
+    SinkExt,
+    channel::mpsc::{Receiver, SendError, Sender},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{Event, Side},
+    game::{Cell, GRID_SIZE, Orientation, ShipType},
+};
+
+/// A game whose events are broadcast to the spectators.
+pub struct Game {
+    /// The public key of the opponent, to tell apart the games of the player.
+    pub opponent: String,
+    /// The events published by the player's game state actor.
+    pub events: Receiver<Event>,
+}
+
+/// The mailbox to add games to the broadcast actor.
+#[derive(Clone)]
+pub struct Mailbox {
+    sender: Sender<Game>,
+}
+
+impl Mailbox {
+    pub fn new(sender: Sender<Game>) -> Self {
+        Self { sender }
+    }
+
+    pub async fn add_game(&mut self, game: Game) -> Result<(), SendError> {
+        self.sender.send(game).await
+    }
+}
+
+/// An update of a game, as seen by the spectators.
+///
+/// NOTE: this only contains what both players know about the game, i.e. the attacks and their results.
+/// The boards with the positions of the ships are only included once the game is over, so that
+/// a spectator can't pass them on to the opponent.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Update {
+    /// The game started and the given player has the first turn.
+    Start { first: Side },
+    /// An attack was sent by the given player.
+    Attack { by: Side, number: u16, x: u8, y: u8 },
+    /// The result of an attack of the given player.
+    Result {
+        by: Side,
+        number: u16,
+        x: u8,
+        y: u8,
+        hit: bool,
+    },
+    /// The game ended with the given winner, or without one in case of an error.
+    End { winner: Option<Side> },
+    /// Both players revealed their ships, so the full boards are shown.
+    GameOver {
+        winner: Side,
+        own_board: Vec<Vec<Cell>>,
+        opponent_board: Vec<Vec<Cell>>,
+    },
+}
+
+impl Update {
+    /// Returns the update for the given event, if it may be shown to the spectators.
+    ///
+    /// The grids are never broadcast, since they contain the positions of the player's ships.
+    /// The sunk ships are not revealed either, since the protocol does not reveal them to the opponent.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Start { first } => Some(Self::Start { first: *first }),
+            Event::Attack {
+                by, number, x, y, ..
+            } => Some(Self::Attack {
+                by: *by,
+                number: *number,
+                x: *x,
+                y: *y,
+            }),
+            Event::Result {
+                by,
+                number,
+                x,
+                y,
+                hit,
+                ..
+            } => Some(Self::Result {
+                by: *by,
+                number: *number,
+                x: *x,
+                y: *y,
+                hit: *hit,
+            }),
+            Event::End { winner } => Some(Self::End { winner: *winner }),
+            Event::GameOver { summary } => Some(Self::GameOver {
+                winner: summary.winner,
+                own_board: summary.own_board.clone(),
+                opponent_board: summary.opponent_board.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Message describes the messages exchanged between the players and the spectators.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum Message {
+    /// Subscribes the spectator to the games of the receiving player.
+    ///
+    /// NOTE: the subscription expires, so it's renewed periodically by the spectator.
+    Subscribe,
+    /// An update of the player's game against the given opponent.
+    Update { opponent: String, update: Update },
+}
+
+impl Message {
+    /// Returns an upper bound of the size of an encoded message, e.g. to validate the maximum
+    /// message size of the p2p network.
+    ///
+    /// NOTE: the largest message is the end of a game, which reveals the boards of both players.
+    pub fn max_encoded_size() -> usize {
+        let board =
+            vec![
+                vec![Cell::Ship(ShipType::Battleship(Orientation::Horizontal)); GRID_SIZE as usize];
+                GRID_SIZE as usize
+            ];
+        let message = Message::Update {
+            opponent: "f".repeat(64),
+            update: Update::GameOver {
+                winner: Side::Opponent,
+                own_board: board.clone(),
+                opponent_board: board,
+            },
+        };
+
+        bytes::Bytes::from(message).len()
+    }
+}
+
+impl From<Message> for bytes::Bytes {
+    fn from(val: Message) -> Self {
+        let serialized = serde_yaml::to_string(&val).expect("failed to serialize message");
+
+        bytes::Bytes::from(serialized.into_bytes())
+    }
+}
+
+/// Decodes a received spectator message.
+///
+/// NOTE: every authorized peer can send spectator messages, so malformed messages must not panic.
+impl TryFrom<bytes::Bytes> for Message {
+    type Error = eyre::Report;
+
+    fn try_from(value: bytes::Bytes) -> eyre::Result<Self> {
+        serde_yaml::from_slice(&value)
+            .map_err(|e| eyre::eyre!("failed to decode spectator message: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        application::{Move, Score, Stats, Summary},
+        game::ShipType,
+        gui::GridView,
+    };
+
+    #[test]
+    fn test_from_event() {
+        let m = Move::new(3, 2, 1, true);
+        assert_eq!(
+            Some(Update::Result {
+                by: Side::Own,
+                number: 3,
+                x: 2,
+                y: 1,
+                hit: true,
+            }),
+            Update::from_event(&Event::result(Side::Own, &m))
+        );
+
+        // the positions of the ships are not revealed before the game is over
+        let ships = vec![vec![Cell::Ship(ShipType::Boat)]];
+        let grids = Event::Grids {
+            own: GridView::new(ships.clone(), None),
+            opponent: GridView::default(),
+        };
+        assert_eq!(None, Update::from_event(&grids));
+        assert_eq!(
+            None,
+            Update::from_event(&Event::Sunk {
+                by: Side::Opponent,
+                ship: "Boat".into(),
+            })
+        );
+
+        let stats = Stats::new(&[], &[]);
+        let summary = Summary {
+            winner: Side::Own,
+            duration: std::time::Duration::ZERO,
+            own: stats.clone(),
+            opponent: stats,
+            series: Score::default(),
+            own_board: ships.clone(),
+            opponent_board: Vec::new(),
+            disputed: false,
+        };
+        assert_eq!(
+            Some(Update::GameOver {
+                winner: Side::Own,
+                own_board: ships,
+                opponent_board: Vec::new(),
+            }),
+            Update::from_event(&Event::GameOver { summary })
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let bytes = bytes::Bytes::from(Message::Subscribe);
+        assert!(matches!(Message::try_from(bytes), Ok(Message::Subscribe)));
+
+        assert!(Message::try_from(bytes::Bytes::from_static(b"\x00invalid")).is_err());
+    }
+}
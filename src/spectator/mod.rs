@@ -0,0 +1,13 @@
+//! Spectators watch the games of the players live, without taking part in them.
+//!
+//! A spectator subscribes to the games of the players on a dedicated channel of the p2p network.
+//! The players broadcast the updates of their games (the attacks and their results) to all
+//! subscribed spectators, which render them read-only. The positions of the ships are only
+//! broadcast once the game is over, so that a spectator can't pass them on to the opponent.
+mod actor;
+mod broadcast;
+mod ingress;
+
+pub use actor::SpectatorActor;
+pub use broadcast::BroadcastActor;
+pub use ingress::{Game, Mailbox, Message, Update};
@@ -0,0 +1,97 @@
+//! Probability density strategy.
+//!
+//! For every field, the strategy counts the number of possible ship placements
+//! covering it, that are consistent with the hits and misses seen so far.
+//! Placements covering open hits are weighted higher, so that the strategy
+//! finishes off hit ships before searching for new ones.
+//! The field with the highest count is attacked next.
+
+use super::{Board, Strategy};
+use crate::game::{Coordinate, ShipType};
+
+/// The additional weight of a placement for every hit that it covers.
+const HIT_WEIGHT: u32 = 10;
+
+#[derive(Debug, Default)]
+pub struct Density;
+
+impl Density {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Computes the number of weighted placements covering every unplayed field.
+    ///
+    /// The scores are indexed by row first, i.e. `(y - 1) * width + (x - 1)`.
+    fn scores(&self, board: &Board) -> Vec<u32> {
+        let width = board.width as usize;
+        let mut scores = vec![0; width * board.height as usize];
+
+        for ship_type in ShipType::variants() {
+            for (x, y) in (1..=board.height).flat_map(|y| (1..=board.width).map(move |x| (x, y))) {
+                let coords = ship_type.get_hitbox(Coordinate::from((x, y)));
+
+                // Placements outside of the grid or covering a miss are not possible.
+                if coords
+                    .iter()
+                    .any(|c| !board.contains(c.x, c.y) || board.result(c.x, c.y) == Some(false))
+                {
+                    continue;
+                }
+
+                let hits = coords
+                    .iter()
+                    .filter(|c| board.result(c.x, c.y) == Some(true))
+                    .count() as u32;
+
+                for c in coords.iter().filter(|c| !board.is_played(c.x, c.y)) {
+                    scores[(c.y as usize - 1) * width + (c.x as usize - 1)] += 1 + hits * HIT_WEIGHT;
+                }
+            }
+        }
+
+        scores
+    }
+}
+
+impl Strategy for Density {
+    fn name(&self) -> &str {
+        "density"
+    }
+
+    fn next_move(&mut self, board: &Board) -> eyre::Result<(u8, u8)> {
+        let scores = self.scores(board);
+        let width = board.width as usize;
+
+        // NOTE: the first field with the highest score is chosen, which keeps the strategy deterministic.
+        board
+            .unplayed()
+            .into_iter()
+            .rev()
+            .max_by_key(|(x, y)| scores[(*y as usize - 1) * width + (*x as usize - 1)])
+            .ok_or_else(|| eyre::eyre!("no fields left to attack"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::Move;
+
+    #[test]
+    fn test_density() {
+        let mut strategy = Density::new();
+
+        // The center of an empty board is covered by the most placements.
+        let moves = vec![];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((3, 3), strategy.next_move(&board).unwrap());
+
+        // Fields next to a hit are preferred.
+        let moves = vec![Move::new(1, 1, 1, true)];
+        let board = Board::new(5, 5, &moves);
+        let (x, y) = strategy.next_move(&board).unwrap();
+        assert!((x, y) == (2, 1) || (x, y) == (1, 2));
+    }
+}
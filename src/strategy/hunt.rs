@@ -0,0 +1,106 @@
+//! Deterministic hunt/target strategy.
+//!
+//! While there is no open hit, the strategy "hunts" by attacking every other field
+//! in a checkerboard pattern, since every ship covers at least two adjacent fields
+//! except for the boats.
+//! Once a ship was hit, it "targets" the neighbouring fields of the most recent hits.
+
+use super::{Board, Strategy};
+
+#[derive(Debug, Default)]
+pub struct HuntTarget;
+
+impl HuntTarget {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the first unplayed neighbour of the most recent hits.
+    fn target(&self, board: &Board) -> Option<(u8, u8)> {
+        board
+            .moves
+            .iter()
+            .rev()
+            .filter(|m| m.is_hit)
+            .flat_map(|m| {
+                let (x, y) = (m.get_x(), m.get_y());
+                [
+                    (x.checked_sub(1), Some(y)),
+                    (x.checked_add(1), Some(y)),
+                    (Some(x), y.checked_sub(1)),
+                    (Some(x), y.checked_add(1)),
+                ]
+            })
+            .filter_map(|(x, y)| Some((x?, y?)))
+            .find(|(x, y)| board.contains(*x, *y) && !board.is_played(*x, *y))
+    }
+
+    /// Returns the next unplayed field of the checkerboard pattern,
+    /// or any unplayed field if the pattern is exhausted.
+    fn hunt(&self, board: &Board) -> Option<(u8, u8)> {
+        let unplayed = board.unplayed();
+
+        unplayed
+            .iter()
+            .find(|(x, y)| (x + y) % 2 == 0)
+            .or(unplayed.first())
+            .copied()
+    }
+}
+
+impl Strategy for HuntTarget {
+    fn name(&self) -> &str {
+        "hunt"
+    }
+
+    fn next_move(&mut self, board: &Board) -> eyre::Result<(u8, u8)> {
+        self.target(board)
+            .or_else(|| self.hunt(board))
+            .ok_or_else(|| eyre::eyre!("no fields left to attack"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::Move;
+
+    #[test]
+    fn test_hunt() {
+        let mut strategy = HuntTarget::new();
+
+        let moves = vec![];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((1, 1), strategy.next_move(&board).unwrap());
+
+        let moves = vec![Move::new(1, 1, 1, false)];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((3, 1), strategy.next_move(&board).unwrap());
+    }
+
+    #[test]
+    fn test_target() {
+        let mut strategy = HuntTarget::new();
+
+        let moves = vec![Move::new(1, 3, 3, true), Move::new(3, 2, 3, false)];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((4, 3), strategy.next_move(&board).unwrap());
+
+        // hits on the edge of the grid must not yield coordinates outside the grid
+        let moves = vec![Move::new(1, 1, 1, true)];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((2, 1), strategy.next_move(&board).unwrap());
+    }
+
+    #[test]
+    fn test_exhausted() {
+        let mut strategy = HuntTarget::new();
+
+        let moves = (1..=2)
+            .flat_map(|y| (1..=2).map(move |x| Move::new(0, x, y, false)))
+            .collect::<Vec<Move>>();
+        let board = Board::new(2, 2, &moves);
+        assert!(strategy.next_move(&board).is_err());
+    }
+}
@@ -0,0 +1,467 @@
+//! Strategy using an LLM model to compute the next move.
+//!
+//! The model output is not guaranteed to be usable, so every prompt is
+//! retried a bounded number of times, giving the model feedback about
+//! why its previous answer was rejected.
+//! If the model does not produce a valid move in time, the deterministic
+//! [`HuntTarget`] strategy is used instead.
+
+use std::{
+    fmt,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use commonware_runtime::Metrics;
+use parrot::llm::Model;
+use prometheus_client::metrics::counter::Counter;
+use regex::Regex;
+
+use super::{Board, HuntTarget, Strategy};
+use crate::game::Coordinate;
+use crate::gui::{Log, LogType};
+
+/// Configuration of the retry behaviour when prompting the LLM.
+#[derive(Clone, Debug)]
+pub struct LlmConfig {
+    /// The maximum number of prompts per move, before falling back to the deterministic strategy.
+    pub max_attempts: usize,
+    /// The maximum duration to wait for a single prompt to return.
+    pub timeout: Duration,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Counters for the failed interactions with the LLM.
+#[derive(Clone, Debug, Default)]
+pub struct LlmMetrics {
+    /// Number of prompts that returned an error.
+    pub errors: Counter,
+    /// Number of prompts that did not return in time.
+    pub timeouts: Counter,
+    /// Number of responses that did not contain a coordinate on the grid.
+    pub malformed: Counter,
+    /// Number of responses that suggested an already played move.
+    pub repeated: Counter,
+    /// Number of moves that were computed by the fallback strategy.
+    pub fallbacks: Counter,
+}
+
+impl LlmMetrics {
+    /// Registers the counters with the given runtime context.
+    pub fn register(&self, context: &impl Metrics) {
+        context.register("errors", "failed llm prompts", self.errors.clone());
+        context.register("timeouts", "timed out llm prompts", self.timeouts.clone());
+        context.register(
+            "malformed",
+            "llm responses without a valid coordinate",
+            self.malformed.clone(),
+        );
+        context.register(
+            "repeated",
+            "llm responses with an already played move",
+            self.repeated.clone(),
+        );
+        context.register(
+            "fallbacks",
+            "moves computed by the fallback strategy",
+            self.fallbacks.clone(),
+        );
+    }
+}
+
+/// The reasons why a prompt did not yield a usable move.
+#[derive(Debug, PartialEq)]
+enum Failure {
+    Error(String),
+    Timeout,
+    Malformed(String),
+    Repeated(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Error(e) => write!(f, "the request failed ({})", e),
+            Failure::Timeout => write!(f, "you did not answer in time"),
+            Failure::Malformed(output) => write!(
+                f,
+                "your answer '{}' did not contain a coordinate on the grid",
+                output
+            ),
+            Failure::Repeated(coord) => {
+                write!(f, "the field {} was already attacked before", coord)
+            }
+        }
+    }
+}
+
+/// The result of a single prompt, with the error already converted to a message.
+type Response = Result<String, String>;
+
+pub struct LlmStrategy {
+    // NOTE: the prompts are run on a single worker thread, since a prompt that timed out
+    // can't be cancelled. The worker is only handed the next prompt once the abandoned one
+    // returned, so that timed out prompts can't pile up.
+    worker: mpsc::Sender<(String, mpsc::Sender<Response>)>,
+    /// The response of the prompt that timed out and is still running on the worker, if any.
+    abandoned: Option<mpsc::Receiver<Response>>,
+    config: LlmConfig,
+    metrics: LlmMetrics,
+    fallback: HuntTarget,
+    /// The logs of the previous moves, which are published by the caller.
+    logs: Vec<Log>,
+}
+
+impl LlmStrategy {
+    pub fn new(model: Box<dyn Model>, config: LlmConfig, metrics: LlmMetrics) -> Self {
+        let (worker, prompts) = mpsc::channel::<(String, mpsc::Sender<Response>)>();
+
+        // NOTE: the worker stops once the strategy is dropped, after its running prompt returned.
+        thread::spawn(move || {
+            for (prompt, response) in prompts {
+                // NOTE: the receiver is dropped if the prompt was abandoned, so we ignore the error here.
+                let _ = response.send(model.prompt(&prompt).map_err(|e| e.to_string()));
+            }
+        });
+
+        Self {
+            worker,
+            abandoned: None,
+            config,
+            metrics,
+            fallback: HuntTarget::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    /// Sends the prompt to the model, waiting at most for the configured timeout.
+    ///
+    /// If a previous prompt timed out, the timeout includes waiting for it to return first.
+    fn prompt(&mut self, prompt: String) -> Result<String, Failure> {
+        let deadline = Instant::now() + self.config.timeout;
+        let remaining = || deadline.saturating_duration_since(Instant::now());
+
+        if let Some(abandoned) = &self.abandoned {
+            match abandoned.recv_timeout(remaining()) {
+                Err(mpsc::RecvTimeoutError::Timeout) => return Err(Failure::Timeout),
+                _ => self.abandoned = None,
+            }
+        }
+
+        let (tx, rx) = mpsc::channel();
+        self.worker
+            .send((prompt, tx))
+            .map_err(|_| Failure::Error("the model stopped".into()))?;
+
+        match rx.recv_timeout(remaining()) {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(e)) => Err(Failure::Error(e)),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                self.abandoned = Some(rx);
+                Err(Failure::Timeout)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(Failure::Error("the model stopped".into()))
+            }
+        }
+    }
+
+    /// Prompts the model once and validates the returned move.
+    fn attempt(&mut self, board: &Board, failures: &[Failure]) -> Result<(u8, u8), Failure> {
+        let output = self.prompt(build_prompt(board, failures))?;
+        self.logs.push(Log::new(
+            LogType::Debug,
+            format!("got LLM result: {}", output.trim()),
+        ));
+
+        match parse_coordinate(&output, board.width, board.height) {
+            Some((x, y)) if board.is_played(x, y) => Err(Failure::Repeated(
+                Coordinate::from((x, y)).to_string(),
+            )),
+            Some(coord) => Ok(coord),
+            None => Err(Failure::Malformed(output.trim().to_string())),
+        }
+    }
+
+    fn record(&self, failure: &Failure) {
+        match failure {
+            Failure::Error(_) => self.metrics.errors.inc(),
+            Failure::Timeout => self.metrics.timeouts.inc(),
+            Failure::Malformed(_) => self.metrics.malformed.inc(),
+            Failure::Repeated(_) => self.metrics.repeated.inc(),
+        };
+    }
+}
+
+impl Strategy for LlmStrategy {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    fn next_move(&mut self, board: &Board) -> eyre::Result<(u8, u8)> {
+        let mut failures = Vec::new();
+
+        while failures.len() < self.config.max_attempts {
+            match self.attempt(board, &failures) {
+                Ok(coord) => return Ok(coord),
+                Err(failure) => {
+                    self.logs.push(Log::new(
+                        LogType::Info,
+                        format!("rejected LLM move: {}", failure),
+                    ));
+                    self.record(&failure);
+                    failures.push(failure);
+                }
+            }
+        }
+
+        self.metrics.fallbacks.inc();
+        self.fallback.next_move(board)
+    }
+
+    fn take_logs(&mut self) -> Vec<Log> {
+        std::mem::take(&mut self.logs)
+    }
+}
+
+/// Returns the first available LLM model whose name contains the given name.
+pub fn find_model(name: &str) -> eyre::Result<Box<dyn Model>> {
+    let name = name.to_lowercase();
+    let mut available_models: Vec<Box<dyn Model>> = parrot::llm::get_available_models()
+        .map_err(|e| eyre::eyre!("failed to get available ai models: {:?}", e))?
+        .into_iter()
+        .filter(|m| m.get_name().to_lowercase().contains(&name))
+        .collect();
+
+    if available_models.is_empty() {
+        return Err(eyre::eyre!("no available llms found matching {}", name));
+    }
+
+    // NOTE: here we're using .remove(0) to create an owned copy of the Box. When indexing the vector using [0], it's returning a borrowed instance.
+    Ok(available_models.remove(0))
+}
+
+/// Builds the prompt containing the grid size and all previously played moves.
+///
+/// If previous attempts for the same move failed, the reasons are appended
+/// so that the model can correct its answer.
+fn build_prompt(board: &Board, failures: &[Failure]) -> String {
+    let played_moves = board
+        .moves
+        .iter()
+        .map(|m| {
+            format!(
+                "{} ({})",
+                m.get_position(),
+                if m.is_hit { "hit" } else { "miss" }
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    let mut prompt = format!(
+        r#"
+            You're playing a game of battleship on a {}x{} grid.
+            The columns are labeled from A to {} and the rows from 1 to {}.
+            You're supposed to identify the next move that's reasonable for you to win this game.
+            DO NOT create any code.
+            You MUST purely provide a tactically sensible move as the output of this prompt.
+            I am going to provide the list of past moves to you and you need to decide on the next move to play.
+            If no previous moves have been played, just attack a random field in the grid.
+            The past moves have been the following: ({}).
+            You MUST NOT attack a field that was already attacked.
+            You MUST ONLY return the next field in the form of e.g. 'A1', 'B2', etc. and nothing else!!
+            This output will be parsed so it's mandatory to NOT INCLUDE ANYTHING EXCEPT THE COORDINATE!!!
+            (no comments, no formatting, NOTHING)
+            "#,
+        board.width,
+        board.height,
+        char::from(b'A' + board.width.saturating_sub(1)),
+        board.height,
+        played_moves,
+    );
+
+    for failure in failures {
+        prompt.push_str(&format!(
+            "\n            Your previous answer was rejected because {}.",
+            failure
+        ));
+    }
+
+    prompt
+}
+
+/// Parses the first coordinate on the grid from the given LLM output.
+///
+/// Both upper- and lowercase column names as well as multi-digit rows are supported,
+/// e.g. "B2", "c4" or "J10".
+pub fn parse_coordinate(output: &str, width: u8, height: u8) -> Option<(u8, u8)> {
+    let re = Regex::new(r"(?i)\b([a-z])\s?([0-9]{1,2})\b").expect("invalid regex");
+
+    re.captures_iter(output)
+        .filter_map(|c| Coordinate::try_from(format!("{}{}", &c[1], &c[2])).ok())
+        .map(|c| (c.x, c.y))
+        .find(|(x, y)| (1..=width).contains(x) && (1..=height).contains(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use parrot::errors::Error as LlmError;
+
+    use crate::application::Move;
+    use crate::strategy::MockModel;
+
+    /// A model that only answers once it's released, counting the started prompts.
+    struct BlockingModel {
+        started: Arc<AtomicUsize>,
+        release: Mutex<mpsc::Receiver<()>>,
+    }
+
+    impl Model for BlockingModel {
+        fn get_name(&self) -> String {
+            "blocking".into()
+        }
+
+        fn prompt(&self, _: &str) -> Result<String, LlmError> {
+            self.started.fetch_add(1, Ordering::Relaxed);
+            let _ = self.release.lock().unwrap().recv();
+
+            Ok("C4".into())
+        }
+    }
+
+    #[test]
+    fn test_parse_coordinate() {
+        let test_cases = [
+            ("A1", Some((1, 1))),
+            ("  c4\n", Some((3, 4))),
+            ("The next move is: E5.", Some((5, 5))),
+            ("B 2", Some((2, 2))),
+            ("J10", None),
+            ("F1, then B3", Some((2, 3))),
+            ("no idea", None),
+            ("", None),
+        ];
+
+        for (output, expected) in test_cases {
+            assert_eq!(expected, parse_coordinate(output, 5, 5), "{}", output);
+        }
+
+        assert_eq!(Some((10, 10)), parse_coordinate("J10", 10, 10));
+    }
+
+    #[test]
+    fn test_build_prompt() {
+        let moves = vec![Move::new(1, 2, 3, true)];
+        let board = Board::new(5, 5, &moves);
+
+        let prompt = build_prompt(&board, &[]);
+        assert!(prompt.contains("5x5 grid"));
+        assert!(prompt.contains("from A to E"));
+        assert!(prompt.contains("B3 (hit)"));
+        assert!(!prompt.contains("rejected"));
+
+        let prompt = build_prompt(&board, &[Failure::Repeated("B3".into()), Failure::Timeout]);
+        assert!(prompt.contains("the field B3 was already attacked before"));
+        assert!(prompt.contains("you did not answer in time"));
+    }
+
+    #[test]
+    fn test_retries() {
+        let model = MockModel::replay(vec!["no idea".into(), "B3".into(), "c4".into()]);
+        let metrics = LlmMetrics::default();
+        let mut strategy = LlmStrategy::new(Box::new(model), LlmConfig::default(), metrics.clone());
+
+        let moves = vec![Move::new(1, 2, 3, true)];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((3, 4), strategy.next_move(&board).unwrap());
+        assert_eq!(1, metrics.malformed.get());
+        assert_eq!(1, metrics.repeated.get());
+        assert_eq!(0, metrics.fallbacks.get());
+    }
+
+    #[test]
+    fn test_fallback() {
+        let model = MockModel::random(1.0, 0);
+        let metrics = LlmMetrics::default();
+        let mut strategy = LlmStrategy::new(Box::new(model), LlmConfig::default(), metrics.clone());
+
+        let moves = vec![];
+        let board = Board::new(5, 5, &moves);
+        assert_eq!((1, 1), strategy.next_move(&board).unwrap());
+        assert_eq!(3, metrics.malformed.get());
+        assert_eq!(1, metrics.fallbacks.get());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let (release, receiver) = mpsc::channel();
+        let model = BlockingModel {
+            started: started.clone(),
+            release: Mutex::new(receiver),
+        };
+        let config = LlmConfig {
+            max_attempts: 3,
+            timeout: Duration::from_millis(50),
+        };
+        let metrics = LlmMetrics::default();
+        let mut strategy = LlmStrategy::new(Box::new(model), config, metrics.clone());
+
+        let moves = vec![];
+        let board = Board::new(5, 5, &moves);
+
+        // the retries wait for the prompt that timed out instead of starting new ones
+        assert_eq!((1, 1), strategy.next_move(&board).unwrap());
+        assert_eq!(1, started.load(Ordering::Relaxed));
+        assert_eq!(3, metrics.timeouts.get());
+        assert_eq!(1, metrics.fallbacks.get());
+
+        let logs = strategy.take_logs();
+        assert_eq!(3, logs.len());
+        for log in logs {
+            assert!(log.content().contains("did not answer in time"));
+        }
+        assert!(strategy.take_logs().is_empty());
+
+        // once the abandoned prompt returned, its answer is discarded and the model is prompted again
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+        assert_eq!((3, 4), strategy.next_move(&board).unwrap());
+        assert_eq!(2, started.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_random_mock() {
+        let model = MockModel::random(0.0, 0);
+        let mut strategy = LlmStrategy::new(
+            Box::new(model),
+            LlmConfig::default(),
+            LlmMetrics::default(),
+        );
+
+        let mut moves = vec![];
+        for number in 1..=25 {
+            let board = Board::new(5, 5, &moves);
+            let (x, y) = strategy.next_move(&board).unwrap();
+            assert!(board.contains(x, y) && !board.is_played(x, y));
+            moves.push(Move::new(number, x, y, false));
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Offline mock of an LLM model.
+//!
+//! This allows running the full LLM code path of the [`LlmStrategy`](super::LlmStrategy)
+//! without having an actual model installed, e.g. in tests, CI or for demos.
+
+use std::sync::{
+    Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use parrot::{errors::Error as LlmError, llm::Model};
+use regex::Regex;
+
+use crate::game::Coordinate;
+
+/// Responses that the mock model returns when it's supposed to produce malformed output.
+const MALFORMED_RESPONSES: [&str; 3] = [
+    "I would attack the center of the grid.",
+    "```rust\nfn main() {}\n```",
+    "Z99",
+];
+
+/// Available modes of the mock model.
+enum MockMode {
+    /// Replays the given canned responses in order, starting again from the
+    /// beginning once all responses were returned.
+    Replay(Vec<String>),
+    /// Returns random fields that were not played yet, based on the contents of the prompt.
+    /// With the given rate (between 0 and 1) a malformed response is returned instead.
+    Random { malformed_rate: f64 },
+}
+
+pub struct MockModel {
+    mode: MockMode,
+    /// The index of the next canned response to return.
+    cursor: AtomicUsize,
+    rng: Mutex<fastrand::Rng>,
+}
+
+impl MockModel {
+    /// Constructs a mock model replaying the given responses.
+    pub fn replay(responses: Vec<String>) -> Self {
+        assert!(!responses.is_empty(), "no responses to replay");
+
+        Self {
+            mode: MockMode::Replay(responses),
+            cursor: AtomicUsize::new(0),
+            rng: Mutex::new(fastrand::Rng::new()),
+        }
+    }
+
+    /// Constructs a mock model replaying the responses from the given file.
+    ///
+    /// Every non-empty line of the file is used as a single response.
+    pub fn from_file(filepath: &str) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(std::path::Path::new(filepath))?;
+        let responses = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect::<Vec<String>>();
+
+        if responses.is_empty() {
+            return Err(eyre::eyre!("no responses found in {}", filepath));
+        }
+
+        Ok(Self::replay(responses))
+    }
+
+    /// Constructs a mock model returning random unplayed fields.
+    pub fn random(malformed_rate: f64, seed: u64) -> Self {
+        Self {
+            mode: MockMode::Random {
+                malformed_rate: malformed_rate.clamp(0.0, 1.0),
+            },
+            cursor: AtomicUsize::new(0),
+            rng: Mutex::new(fastrand::Rng::with_seed(seed)),
+        }
+    }
+
+    /// Picks a random field that was not played yet.
+    ///
+    /// The grid size and the played moves are extracted from the prompt.
+    fn random_move(&self, prompt: &str, rng: &mut fastrand::Rng) -> String {
+        let (width, height) = Regex::new(r"(\d+)x(\d+) grid")
+            .expect("invalid regex")
+            .captures(prompt)
+            .and_then(|c| Some((c[1].parse::<u8>().ok()?, c[2].parse::<u8>().ok()?)))
+            .unwrap_or((crate::game::GRID_SIZE, crate::game::GRID_SIZE));
+
+        let played = Regex::new(r"\b([A-Z][0-9]{1,2}) \((?:hit|miss)\)")
+            .expect("invalid regex")
+            .captures_iter(prompt)
+            .map(|c| c[1].to_string())
+            .collect::<Vec<String>>();
+
+        let unplayed = (1..=height)
+            .flat_map(|y| (1..=width).map(move |x| Coordinate::from((x, y)).to_string()))
+            .filter(|field| !played.contains(field))
+            .collect::<Vec<String>>();
+
+        match unplayed.is_empty() {
+            true => "A1".into(),
+            false => unplayed[rng.usize(..unplayed.len())].clone(),
+        }
+    }
+}
+
+impl Model for MockModel {
+    fn get_name(&self) -> String {
+        "mock".into()
+    }
+
+    fn prompt(&self, input: &str) -> Result<String, LlmError> {
+        match &self.mode {
+            MockMode::Replay(responses) => {
+                let index = self.cursor.fetch_add(1, Ordering::Relaxed);
+                Ok(responses[index % responses.len()].clone())
+            }
+            MockMode::Random { malformed_rate } => {
+                let mut rng = self.rng.lock().expect("failed to lock rng");
+                if rng.f64() < *malformed_rate {
+                    return Ok(MALFORMED_RESPONSES[rng.usize(..MALFORMED_RESPONSES.len())].into());
+                }
+
+                Ok(self.random_move(input, &mut rng))
+            }
+        }
+    }
+}
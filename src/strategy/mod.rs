@@ -0,0 +1,110 @@
+//! Strategies to compute the next move to play.
+//!
+//! The game state actor does not decide on the attacked fields itself,
+//! but delegates this to an implementation of the [`Strategy`] trait.
+mod density;
+mod hunt;
+mod llm;
+mod mock;
+mod random;
+
+pub use density::Density;
+pub use hunt::HuntTarget;
+pub use llm::{LlmConfig, LlmMetrics, LlmStrategy, find_model, parse_coordinate};
+pub use mock::MockModel;
+pub use random::RandomStrategy;
+
+use crate::application::Move;
+use crate::gui::Log;
+
+/// The attacker's view of the opponent's board, which is used to decide on the next move.
+pub struct Board<'a> {
+    /// Width of the opponent's grid.
+    pub width: u8,
+    /// Height of the opponent's grid.
+    pub height: u8,
+    /// The moves that were already played against the opponent.
+    pub moves: &'a [Move],
+}
+
+impl<'a> Board<'a> {
+    pub fn new(width: u8, height: u8, moves: &'a [Move]) -> Self {
+        Self {
+            width,
+            height,
+            moves,
+        }
+    }
+
+    /// Checks if the given coordinate is located on the grid.
+    ///
+    /// NOTE: the existing battleship-rs logic uses indices from 1..=grid_size.
+    pub fn contains(&self, x: u8, y: u8) -> bool {
+        (1..=self.width).contains(&x) && (1..=self.height).contains(&y)
+    }
+
+    /// Checks if the given coordinate was already attacked.
+    pub fn is_played(&self, x: u8, y: u8) -> bool {
+        self.moves.iter().any(|m| m.get_x() == x && m.get_y() == y)
+    }
+
+    /// Returns if the attack on the given coordinate was a hit,
+    /// or `None` if the coordinate was not attacked yet.
+    pub fn result(&self, x: u8, y: u8) -> Option<bool> {
+        self.moves
+            .iter()
+            .find(|m| m.get_x() == x && m.get_y() == y)
+            .map(|m| m.is_hit)
+    }
+
+    /// Returns all coordinates that were not attacked yet, ordered row by row.
+    pub fn unplayed(&self) -> Vec<(u8, u8)> {
+        (1..=self.height)
+            .flat_map(|y| (1..=self.width).map(move |x| (x, y)))
+            .filter(|(x, y)| !self.is_played(*x, *y))
+            .collect()
+    }
+}
+
+/// A strategy computes the next field to attack based on the current board.
+///
+/// Implementations MUST only return coordinates that are on the grid
+/// and were not played before.
+pub trait Strategy: Send {
+    /// Returns a human-readable name of the strategy.
+    fn name(&self) -> &str;
+
+    /// Computes the next move to play as (x, y) coordinates.
+    fn next_move(&mut self, board: &Board) -> eyre::Result<(u8, u8)>;
+
+    /// Returns the logs collected while computing the previous moves (e.g. the rejected
+    /// answers of an LLM), so that the caller can publish them in its event log.
+    fn take_logs(&mut self) -> Vec<Log> {
+        Vec::new()
+    }
+}
+
+/// Constructs the registered strategy with the given name.
+///
+/// Besides the named strategies, `llm:<model>` selects the LLM model whose name contains `<model>`.
+/// The seed is used by the randomized strategies, so that games can be reproduced.
+/// The LLM strategies record their failures in the given metrics.
+pub fn from_name(name: &str, seed: u64, metrics: &LlmMetrics) -> eyre::Result<Box<dyn Strategy>> {
+    let model: Box<dyn parrot::llm::Model> = match name.to_lowercase().as_str() {
+        "hunt" => return Ok(Box::new(HuntTarget::new())),
+        "random" => return Ok(Box::new(RandomStrategy::new(seed))),
+        "density" => return Ok(Box::new(Density::new())),
+        "mock" => Box::new(MockModel::random(0.1, seed)),
+        "llm" => find_model("")?,
+        other => match other.strip_prefix("llm:") {
+            Some(model) => find_model(model)?,
+            None => return Err(eyre::eyre!("unknown strategy: {}", name)),
+        },
+    };
+
+    Ok(Box::new(LlmStrategy::new(
+        model,
+        LlmConfig::default(),
+        metrics.clone(),
+    )))
+}
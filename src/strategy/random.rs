@@ -0,0 +1,33 @@
+//! Strategy attacking random fields.
+//!
+//! This serves as the baseline that every other strategy should beat.
+
+use super::{Board, Strategy};
+
+#[derive(Debug)]
+pub struct RandomStrategy {
+    rng: fastrand::Rng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: fastrand::Rng::with_seed(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn next_move(&mut self, board: &Board) -> eyre::Result<(u8, u8)> {
+        let unplayed = board.unplayed();
+        if unplayed.is_empty() {
+            return Err(eyre::eyre!("no fields left to attack"));
+        }
+
+        Ok(unplayed[self.rng.usize(..unplayed.len())])
+    }
+}
@@ -0,0 +1,295 @@
+//! Headless tournament between the registered strategies.
+//!
+//! Every pair of strategies plays a number of seeded games against each other,
+//! without any p2p communication or TUI involved.
+//! The results are used to compare strategies, e.g. to check if a change
+//! of the LLM prompt actually improved the play.
+
+use std::fmt;
+
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{
+    application::Move,
+    game::{GRID_SIZE, Player},
+    strategy::{self, Board, LlmMetrics, Strategy},
+};
+
+/// The z-score of the 95% confidence intervals.
+const Z: f64 = 1.96;
+
+/// Configuration of a tournament.
+#[derive(Clone, Debug)]
+pub struct TournamentConfig {
+    /// The names of the competing strategies.
+    pub strategies: Vec<String>,
+    /// The number of games played between every pair of strategies.
+    pub games: usize,
+}
+
+/// The outcome of a single game.
+#[derive(Debug, PartialEq)]
+pub struct GameResult {
+    /// The index of the winning player.
+    pub winner: usize,
+    /// The number of shots the winner fired.
+    pub shots: usize,
+}
+
+/// Estimation of a value with its 95% confidence interval.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Estimate {
+    pub value: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Estimate {
+    /// Estimates a proportion using the Wilson score interval.
+    pub fn proportion(successes: usize, trials: usize) -> Option<Self> {
+        if trials == 0 {
+            return None;
+        }
+
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let denominator = 1.0 + Z * Z / n;
+        let center = (p + Z * Z / (2.0 * n)) / denominator;
+        let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+
+        Some(Self {
+            value: p,
+            lower: center - margin,
+            upper: center + margin,
+        })
+    }
+
+    /// Estimates the mean of the given samples using the normal approximation.
+    pub fn mean(samples: &[usize]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<usize>() as f64 / n;
+        let variance = match samples.len() {
+            1 => 0.0,
+            _ => {
+                samples
+                    .iter()
+                    .map(|s| (*s as f64 - mean).powi(2))
+                    .sum::<f64>()
+                    / (n - 1.0)
+            }
+        };
+        let margin = Z * (variance / n).sqrt();
+
+        Some(Self {
+            value: mean,
+            lower: mean - margin,
+            upper: mean + margin,
+        })
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} [{:.2}, {:.2}]", self.value, self.lower, self.upper)
+    }
+}
+
+/// The results of all games between two strategies.
+#[derive(Debug, Serialize)]
+pub struct Matchup {
+    /// The names of both strategies.
+    pub strategies: [String; 2],
+    /// The number of played games.
+    pub games: usize,
+    /// The number of won games per strategy.
+    pub wins: [usize; 2],
+    /// The win rate of the first strategy.
+    pub win_rate: Option<Estimate>,
+    /// The mean number of shots per strategy to win a game.
+    pub shots_to_win: [Option<Estimate>; 2],
+}
+
+/// The results of a full tournament.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub matchups: Vec<Matchup>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format_estimate =
+            |e: &Option<Estimate>| e.as_ref().map_or("-".to_string(), |e| e.to_string());
+
+        writeln!(
+            f,
+            "{:<10} {:<10} {:>6} {:>6} {:>6} {:>26} {:>26} {:>26}",
+            "first", "second", "games", "wins", "losses", "win rate", "shots first", "shots second"
+        )?;
+
+        for matchup in &self.matchups {
+            writeln!(
+                f,
+                "{:<10} {:<10} {:>6} {:>6} {:>6} {:>26} {:>26} {:>26}",
+                matchup.strategies[0],
+                matchup.strategies[1],
+                matchup.games,
+                matchup.wins[0],
+                matchup.wins[1],
+                format_estimate(&matchup.win_rate),
+                format_estimate(&matchup.shots_to_win[0]),
+                format_estimate(&matchup.shots_to_win[1]),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays a single game between the given strategies, with the given player starting.
+///
+/// NOTE: the ship placements are drawn from `fastrand`, so the caller has to seed it
+/// to reproduce a game.
+pub fn play_game(strategies: &mut [Box<dyn Strategy>; 2], first: usize) -> eyre::Result<GameResult> {
+    let mut players = [Player::new(), Player::new()];
+    let mut moves: [Vec<Move>; 2] = [Vec::new(), Vec::new()];
+    let mut turn = first;
+
+    for number in 1..=(2 * GRID_SIZE as u16 * GRID_SIZE as u16) {
+        let opponent = 1 - turn;
+        let board = Board::new(GRID_SIZE, GRID_SIZE, &moves[turn]);
+        let (x, y) = strategies[turn].next_move(&board)?;
+        // NOTE: there's no event log in a headless tournament, so the logs are dropped.
+        strategies[turn].take_logs();
+
+        if !board.contains(x, y) || board.is_played(x, y) {
+            return Err(eyre::eyre!(
+                "strategy {} returned invalid move: ({},{})",
+                strategies[turn].name(),
+                x,
+                y
+            ));
+        }
+
+        let is_hit = players[opponent].handle_attack(x, y);
+        players[turn].attack(x, y, is_hit)?;
+        moves[turn].push(Move::new(number, x, y, is_hit));
+
+        if players[opponent].lost() {
+            return Ok(GameResult {
+                winner: turn,
+                shots: moves[turn].len(),
+            });
+        }
+
+        turn = opponent;
+    }
+
+    Err(eyre::eyre!("game did not finish"))
+}
+
+/// Plays the configured number of games between every pair of strategies.
+///
+/// The given randomness source is used to seed every game, so running the tournament
+/// e.g. on the deterministic runtime with the same seed yields the same results.
+pub fn run(rng: &mut impl Rng, config: &TournamentConfig) -> eyre::Result<Report> {
+    // NOTE: the LLM failures are not reported, so the metrics are shared between all games.
+    let metrics = LlmMetrics::default();
+    let mut matchups = Vec::new();
+
+    for (i, first) in config.strategies.iter().enumerate() {
+        for second in config.strategies.iter().skip(i + 1) {
+            let mut wins = [0; 2];
+            let mut shots: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+
+            for game in 0..config.games {
+                let seed = rng.r#gen::<u64>();
+                fastrand::seed(seed);
+
+                let mut strategies = [
+                    strategy::from_name(first, seed, &metrics)?,
+                    strategy::from_name(second, seed.wrapping_add(1), &metrics)?,
+                ];
+
+                // The starting player is alternated to not favor either strategy.
+                let result = play_game(&mut strategies, game % 2)?;
+                wins[result.winner] += 1;
+                shots[result.winner].push(result.shots);
+            }
+
+            matchups.push(Matchup {
+                strategies: [first.clone(), second.clone()],
+                games: config.games,
+                wins,
+                win_rate: Estimate::proportion(wins[0], config.games),
+                shots_to_win: [Estimate::mean(&shots[0]), Estimate::mean(&shots[1])],
+            });
+        }
+    }
+
+    Ok(Report { matchups })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commonware_runtime::{Runner, deterministic};
+
+    #[test]
+    fn test_estimate() {
+        assert_eq!(None, Estimate::proportion(0, 0));
+        assert_eq!(None, Estimate::mean(&[]));
+
+        let estimate = Estimate::proportion(50, 100).unwrap();
+        assert_eq!(0.5, estimate.value);
+        assert!((estimate.lower - 0.4038).abs() < 1e-3);
+        assert!((estimate.upper - 0.5962).abs() < 1e-3);
+
+        let estimate = Estimate::mean(&[10, 12, 14]).unwrap();
+        assert_eq!(12.0, estimate.value);
+        assert!((estimate.upper - 14.263).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_play_game() {
+        fastrand::seed(0);
+        let mut strategies: [Box<dyn Strategy>; 2] = [
+            Box::new(strategy::HuntTarget::new()),
+            Box::new(strategy::RandomStrategy::new(0)),
+        ];
+
+        let result = play_game(&mut strategies, 0).unwrap();
+        assert!(result.winner < 2);
+        assert!(result.shots <= (GRID_SIZE * GRID_SIZE) as usize);
+    }
+
+    #[test]
+    fn test_run_deterministic() {
+        let config = TournamentConfig {
+            strategies: vec!["hunt".into(), "random".into(), "density".into()],
+            games: 20,
+        };
+
+        let run_seeded = |seed: u64| {
+            let config = config.clone();
+            deterministic::Runner::seeded(seed)
+                .start(|mut context| async move { run(&mut context, &config).unwrap() })
+        };
+
+        let report = run_seeded(0);
+        assert_eq!(3, report.matchups.len());
+        for matchup in &report.matchups {
+            assert_eq!(20, matchup.wins[0] + matchup.wins[1]);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            serde_json::to_string(&run_seeded(0)).unwrap()
+        );
+    }
+}